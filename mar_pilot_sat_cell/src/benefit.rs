@@ -0,0 +1,217 @@
+//! Multi-contaminant mass-balance benefit kernel for the SAT cell. [file:5][file:14]
+//!
+//! Removal load per contaminant is ΔC · Q with the concentration unit
+//! converted to kg/m³ (1 mg/L = 1e-3 kg/m³, 1 ng/L = 1e-9 kg/m³). Loads are
+//! combined through per-kg hazard weights into the benefit B that feeds E;
+//! nitrate has weight 1, so a nitrate-only balance is plain kg/d removed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use response_shard::ker::BenefitKernel;
+use thiserror::Error;
+
+/// Placeholder hazard weights per kg removed, relative to nitrate; pilot data should tighten. [file:14]
+pub const HAZARD_NITRATE: f64 = 1.0;
+pub const HAZARD_PHOSPHORUS: f64 = 10.0;
+pub const HAZARD_PHARMA: f64 = 1.0e4;
+pub const HAZARD_PFAS: f64 = 1.0e6;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum BenefitError {
+    #[error("flow {0} m3/d must be finite and >= 0")]
+    InvalidFlow(f64),
+    #[error("contaminant `{name}` has a non-finite or negative concentration or weight")]
+    InvalidContaminant { name: String },
+    #[error("unknown concentration unit `{0}` (expected mg/L, ug/L or ng/L)")]
+    UnknownUnit(String),
+}
+
+/// Concentration unit of a contaminant reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConcUnit {
+    MgPerL,
+    UgPerL,
+    NgPerL,
+}
+
+impl ConcUnit {
+    /// kg/m³ per one unit of concentration.
+    pub fn kg_per_m3(&self) -> f64 {
+        match self {
+            ConcUnit::MgPerL => 1.0e-3,
+            ConcUnit::UgPerL => 1.0e-6,
+            ConcUnit::NgPerL => 1.0e-9,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConcUnit::MgPerL => "mg/L",
+            ConcUnit::UgPerL => "ug/L",
+            ConcUnit::NgPerL => "ng/L",
+        }
+    }
+}
+
+impl fmt::Display for ConcUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ConcUnit {
+    type Err = BenefitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mg/L" | "mgL" => Ok(ConcUnit::MgPerL),
+            "ug/L" | "µg/L" | "ugL" => Ok(ConcUnit::UgPerL),
+            "ng/L" | "ngL" => Ok(ConcUnit::NgPerL),
+            other => Err(BenefitError::UnknownUnit(other.to_string())),
+        }
+    }
+}
+
+/// In/out concentrations of one contaminant across the SAT cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contaminant {
+    pub name: String,
+    pub unit: ConcUnit,
+    pub c_in: f64,
+    pub c_out: f64,
+    /// Hazard weight per kg removed.
+    pub hazard_weight: f64,
+}
+
+impl Contaminant {
+    pub fn new(name: impl Into<String>, unit: ConcUnit, c_in: f64, c_out: f64, hazard_weight: f64) -> Self {
+        Self {
+            name: name.into(),
+            unit,
+            c_in,
+            c_out,
+            hazard_weight,
+        }
+    }
+
+    pub fn nitrate(c_in_mg_l: f64, c_out_mg_l: f64) -> Self {
+        Self::new("nitrate", ConcUnit::MgPerL, c_in_mg_l, c_out_mg_l, HAZARD_NITRATE)
+    }
+
+    pub fn phosphorus(c_in_mg_l: f64, c_out_mg_l: f64) -> Self {
+        Self::new("phosphorus", ConcUnit::MgPerL, c_in_mg_l, c_out_mg_l, HAZARD_PHOSPHORUS)
+    }
+
+    pub fn pharma(c_in_ng_l: f64, c_out_ng_l: f64) -> Self {
+        Self::new("pharma", ConcUnit::NgPerL, c_in_ng_l, c_out_ng_l, HAZARD_PHARMA)
+    }
+
+    pub fn pfas(c_in_ng_l: f64, c_out_ng_l: f64) -> Self {
+        Self::new("pfas", ConcUnit::NgPerL, c_in_ng_l, c_out_ng_l, HAZARD_PFAS)
+    }
+}
+
+/// Removal of one contaminant and its share of B.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContaminantLoad {
+    pub name: String,
+    pub removed_kg_d: f64,
+    /// `hazard_weight · removed_kg_d`.
+    pub contribution: f64,
+    /// True when c_out > c_in; removal is then counted as 0.
+    pub net_release: bool,
+}
+
+/// Per-contaminant loads and the hazard-weighted benefit B.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MassBalance {
+    pub flow_m3_d: f64,
+    pub loads: Vec<ContaminantLoad>,
+    /// Σ hazard_weight · removed_kg_d, in hazard-weighted kg/d.
+    pub benefit: f64,
+}
+
+impl MassBalance {
+    pub fn compute(flow_m3_d: f64, contaminants: &[Contaminant]) -> Result<Self, BenefitError> {
+        if !flow_m3_d.is_finite() || flow_m3_d < 0.0 {
+            return Err(BenefitError::InvalidFlow(flow_m3_d));
+        }
+        let mut loads = Vec::with_capacity(contaminants.len());
+        for c in contaminants {
+            let values = [c.c_in, c.c_out, c.hazard_weight];
+            if !values.iter().all(|v| v.is_finite() && *v >= 0.0) {
+                return Err(BenefitError::InvalidContaminant { name: c.name.clone() });
+            }
+            let removed_kg_d = (c.c_in - c.c_out).max(0.0) * c.unit.kg_per_m3() * flow_m3_d;
+            loads.push(ContaminantLoad {
+                name: c.name.clone(),
+                removed_kg_d,
+                contribution: c.hazard_weight * removed_kg_d,
+                net_release: c.c_out > c.c_in,
+            });
+        }
+        let benefit = loads.iter().map(|l| l.contribution).sum();
+        Ok(Self { flow_m3_d, loads, benefit })
+    }
+
+    /// Share of B from `name`, in [0,1]; 0 when B is 0.
+    pub fn share(&self, name: &str) -> f64 {
+        if self.benefit == 0.0 {
+            return 0.0;
+        }
+        self.loads
+            .iter()
+            .filter(|l| l.name == name)
+            .map(|l| l.contribution)
+            .sum::<f64>()
+            / self.benefit
+    }
+
+    /// Bounded kernel for `KerCalculator::eco_impact`.
+    pub fn kernel(&self, b_min: f64, b_max: f64) -> BenefitKernel {
+        BenefitKernel {
+            name: "hazard_weighted_removal".into(),
+            units: "kg/d".into(),
+            value: self.benefit,
+            b_min,
+            b_max,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_units_per_contaminant() {
+        let flow = 10_000.0;
+        let mb = MassBalance::compute(
+            flow,
+            &[Contaminant::nitrate(12.0, 3.0), Contaminant::pfas(7.5, 3.9)],
+        )
+        .unwrap();
+        // 9 mg/L · 1e-3 kg/m3 · 1e4 m3/d = 90 kg/d
+        assert!((mb.loads[0].removed_kg_d - 90.0).abs() < 1e-9);
+        // 3.6 ng/L · 1e-9 kg/m3 · 1e4 m3/d = 3.6e-5 kg/d, weighted 36
+        assert!((mb.loads[1].removed_kg_d - 3.6e-5).abs() < 1e-15);
+        assert!((mb.benefit - 126.0).abs() < 1e-9);
+        assert!((mb.share("pfas") - 36.0 / 126.0).abs() < 1e-12);
+        assert_eq!("ng/L".parse::<ConcUnit>().unwrap(), ConcUnit::NgPerL);
+    }
+
+    #[test]
+    fn release_counts_as_zero_and_bad_input_fails() {
+        let mb = MassBalance::compute(100.0, &[Contaminant::phosphorus(0.5, 1.8)]).unwrap();
+        assert_eq!(mb.benefit, 0.0);
+        assert!(mb.loads[0].net_release);
+        assert_eq!(mb.share("phosphorus"), 0.0);
+
+        assert_eq!(MassBalance::compute(-1.0, &[]), Err(BenefitError::InvalidFlow(-1.0)));
+        assert!(matches!(
+            MassBalance::compute(1.0, &[Contaminant::pharma(f64::NAN, 1.0)]),
+            Err(BenefitError::InvalidContaminant { .. })
+        ));
+    }
+}
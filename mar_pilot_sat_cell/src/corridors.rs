@@ -0,0 +1,292 @@
+//! SAT corridor table from `mar.satcell.corridors.v1` rows or CSV. [file:7][file:14]
+//!
+//! Each pilot site runs with its own permit limits, so bands and weights are
+//! data, not literals. Coordinates keep the physical reading, bands and units
+//! next to the normalized `RiskCoord`, whose bands are the physical ones
+//! mapped into r-space (safe → 0, hard → 1).
+
+use response_shard::wire::{AlnRow, WireError};
+use response_shard::RiskCoord;
+use thiserror::Error;
+
+/// ALN particle defining the SAT corridor rows. [file:14]
+pub const SAT_CORRIDOR_PARTICLE: &str = "mar.satcell.corridors.v1";
+
+/// Column order of the CSV form; matches the ALN particle fields.
+pub const SAT_CORRIDOR_CSV_HEADER: [&str; 6] = ["varid", "units", "safe", "gold", "hard", "weight"];
+
+#[derive(Debug, Error)]
+pub enum CorridorTableError {
+    #[error("corridor ALN is invalid: {0}")]
+    Aln(#[from] WireError),
+    #[error("corridor CSV line {line}: {reason}")]
+    Csv { line: usize, reason: String },
+    #[error("corridor `{var_id}` is invalid: {reason}")]
+    InvalidRow { var_id: String, reason: String },
+    #[error("corridor `{var_id}` has zero width: safe {safe} = hard {hard}")]
+    ZeroWidthBand { var_id: String, safe: f64, hard: f64 },
+    #[error("corridor `{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("no corridor row for `{0}`; no corridor, no build")]
+    UnknownVar(String),
+}
+
+/// One corridor row in physical units. [file:14]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatCorridor {
+    pub var_id: String,
+    pub units: String,
+    pub safe: f64,
+    pub gold: f64,
+    pub hard: f64,
+    pub weight: f64,
+}
+
+impl SatCorridor {
+    fn check(&self) -> Result<(), CorridorTableError> {
+        let invalid = |reason: String| CorridorTableError::InvalidRow {
+            var_id: self.var_id.clone(),
+            reason,
+        };
+        if self.var_id.is_empty() {
+            return Err(invalid("empty varid".into()));
+        }
+        if ![self.safe, self.gold, self.hard, self.weight].iter().all(|v| v.is_finite()) {
+            return Err(invalid("non-finite band or weight".into()));
+        }
+        if self.safe == self.hard {
+            return Err(CorridorTableError::ZeroWidthBand {
+                var_id: self.var_id.clone(),
+                safe: self.safe,
+                hard: self.hard,
+            });
+        }
+        if !(self.safe <= self.gold && self.gold <= self.hard) {
+            return Err(invalid(format!(
+                "safe {} <= gold {} <= hard {} does not hold",
+                self.safe, self.gold, self.hard
+            )));
+        }
+        if self.weight < 0.0 {
+            return Err(invalid(format!("negative weight {}", self.weight)));
+        }
+        Ok(())
+    }
+
+    /// r_x ∈ [0,1]: 0 at or below safe, 1 at or above hard, linear between. [file:7]
+    /// A non-finite reading or a row built without `check` maps to 1, never to a safe-looking 0.
+    pub fn normalize(&self, value: f64) -> f64 {
+        if value <= self.safe {
+            0.0
+        } else if value.is_nan() || value >= self.hard || self.hard <= self.safe {
+            1.0
+        } else {
+            (value - self.safe) / (self.hard - self.safe)
+        }
+    }
+
+    /// Normalized coordinate for a physical reading.
+    pub fn coord(&self, raw: f64) -> SatCoord {
+        SatCoord {
+            raw,
+            corridor: self.clone(),
+            coord: RiskCoord {
+                var_id: self.var_id.clone(),
+                value: self.normalize(raw),
+                safe: 0.0,
+                gold: self.normalize(self.gold),
+                hard: 1.0,
+                weight: self.weight,
+            },
+        }
+    }
+}
+
+/// A normalized coordinate with the reading and corridor it came from.
+#[derive(Debug, Clone)]
+pub struct SatCoord {
+    /// Reading in `corridor.units`.
+    pub raw: f64,
+    pub corridor: SatCorridor,
+    pub coord: RiskCoord,
+}
+
+/// Corridor rows for one site, in definition order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatCorridorTable {
+    rows: Vec<SatCorridor>,
+}
+
+impl SatCorridorTable {
+    /// Rejects inconsistent bands and duplicate `var_id`s.
+    pub fn new(rows: Vec<SatCorridor>) -> Result<Self, CorridorTableError> {
+        for (i, row) in rows.iter().enumerate() {
+            row.check()?;
+            if rows[..i].iter().any(|r| r.var_id == row.var_id) {
+                return Err(CorridorTableError::Duplicate(row.var_id.clone()));
+            }
+        }
+        Ok(Self { rows })
+    }
+
+    /// Phoenix pilot bands: HLR 0.05/0.15/0.25 m/d, PFAS 5/10/20 ng/L, temp 15/25/30 °C. [file:14]
+    pub fn phoenix_default() -> Self {
+        let row = |var_id: &str, units: &str, safe, gold, hard, weight| SatCorridor {
+            var_id: var_id.into(),
+            units: units.into(),
+            safe,
+            gold,
+            hard,
+            weight,
+        };
+        Self {
+            rows: vec![
+                row("r_sat", "m/d", 0.05, 0.15, 0.25, 0.4),
+                row("r_pfas", "ng/L", 5.0, 10.0, 20.0, 0.4),
+                row("r_temp", "degC", 15.0, 25.0, 30.0, 0.2),
+            ],
+        }
+    }
+
+    /// Load consecutive `row mar.satcell.corridors.v1 ... end` blocks.
+    pub fn from_aln(input: &str) -> Result<Self, CorridorTableError> {
+        let mut rows = Vec::new();
+        for mut row in AlnRow::parse_all(input, SAT_CORRIDOR_PARTICLE)? {
+            rows.push(SatCorridor {
+                var_id: row.take("varid")?,
+                units: row.take("units")?,
+                safe: row.take_parsed("safe")?,
+                gold: row.take_parsed("gold")?,
+                hard: row.take_parsed("hard")?,
+                weight: row.take_parsed("weight")?,
+            });
+            if let Some(key) = row.remaining_keys().into_iter().next() {
+                return Err(WireError::UnexpectedField(key).into());
+            }
+        }
+        Self::new(rows)
+    }
+
+    /// Load a CSV with header `varid,units,safe,gold,hard,weight` (any column order).
+    pub fn from_csv(input: &str) -> Result<Self, CorridorTableError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+        let (header_line, header) = lines.next().ok_or(CorridorTableError::Csv {
+            line: 1,
+            reason: "missing header".into(),
+        })?;
+        let header: Vec<&str> = header.split(',').map(str::trim).collect();
+        let mut columns = [0usize; 6];
+        for (slot, name) in columns.iter_mut().zip(SAT_CORRIDOR_CSV_HEADER) {
+            *slot = header.iter().position(|h| *h == name).ok_or(CorridorTableError::Csv {
+                line: header_line,
+                reason: format!("missing column `{}`", name),
+            })?;
+        }
+
+        let mut rows = Vec::new();
+        for (line, text) in lines {
+            let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+            if cells.len() != header.len() {
+                return Err(CorridorTableError::Csv {
+                    line,
+                    reason: format!("expected {} cells, found {}", header.len(), cells.len()),
+                });
+            }
+            let number = |col: usize| -> Result<f64, CorridorTableError> {
+                let cell = cells[columns[col]];
+                cell.parse().map_err(|_| CorridorTableError::Csv {
+                    line,
+                    reason: format!("`{}` = `{}` is not a number", SAT_CORRIDOR_CSV_HEADER[col], cell),
+                })
+            };
+            rows.push(SatCorridor {
+                var_id: cells[columns[0]].to_string(),
+                units: cells[columns[1]].to_string(),
+                safe: number(2)?,
+                gold: number(3)?,
+                hard: number(4)?,
+                weight: number(5)?,
+            });
+        }
+        Self::new(rows)
+    }
+
+    pub fn rows(&self) -> &[SatCorridor] {
+        &self.rows
+    }
+
+    pub fn get(&self, var_id: &str) -> Option<&SatCorridor> {
+        self.rows.iter().find(|r| r.var_id == var_id)
+    }
+
+    /// Normalize physical readings keyed by `var_id`; a reading without a row is an error.
+    pub fn risk_coords(&self, readings: &[(&str, f64)]) -> Result<Vec<SatCoord>, CorridorTableError> {
+        readings
+            .iter()
+            .map(|(var_id, raw)| {
+                self.get(var_id)
+                    .map(|c| c.coord(*raw))
+                    .ok_or_else(|| CorridorTableError::UnknownVar(var_id.to_string()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHOENIX_ALN: &str = include_str!("../../aln/particles/mar.satcell.corridors.phoenix2026.aln");
+
+    #[test]
+    fn aln_and_csv_load_the_same_table() {
+        let csv = "# Phoenix 2026\nvarid,units,safe,gold,hard,weight\n\
+                   r_sat,m/d,0.05,0.15,0.25,0.4\n\
+                   r_pfas,ng/L,5,10,20,0.4\n\
+                   r_temp,degC,15,25,30,0.2\n";
+        let from_aln = SatCorridorTable::from_aln(PHOENIX_ALN).unwrap();
+        assert_eq!(from_aln, SatCorridorTable::phoenix_default());
+        assert_eq!(SatCorridorTable::from_csv(csv).unwrap(), from_aln);
+    }
+
+    #[test]
+    fn coords_keep_physical_bands() {
+        let table = SatCorridorTable::phoenix_default();
+        let coords = table.risk_coords(&[("r_pfas", 12.5)]).unwrap();
+        let c = &coords[0];
+        assert_eq!(c.corridor.units, "ng/L");
+        assert_eq!((c.corridor.gold, c.raw), (10.0, 12.5));
+        assert!((c.coord.value - 0.5).abs() < 1e-12);
+        assert!((c.coord.gold - 1.0 / 3.0).abs() < 1e-12);
+
+        assert!(matches!(
+            table.risk_coords(&[("r_pharma", 1.0)]),
+            Err(CorridorTableError::UnknownVar(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_tables() {
+        let bad_bands = "varid,units,safe,gold,hard,weight\nr_sat,m/d,0.3,0.2,0.25,0.4\n";
+        assert!(matches!(
+            SatCorridorTable::from_csv(bad_bands),
+            Err(CorridorTableError::InvalidRow { .. })
+        ));
+        let dup = "varid,units,safe,gold,hard,weight\nr_sat,m/d,0,1,2,1\nr_sat,m/d,0,1,2,1\n";
+        assert!(matches!(SatCorridorTable::from_csv(dup), Err(CorridorTableError::Duplicate(_))));
+        let flat = "varid,units,safe,gold,hard,weight\nr_temp,degC,25,25,25,0.2\n";
+        assert!(matches!(
+            SatCorridorTable::from_csv(flat),
+            Err(CorridorTableError::ZeroWidthBand { safe, .. }) if safe == 25.0
+        ));
+        assert!(matches!(
+            SatCorridorTable::from_csv("varid,units,safe,gold,hard\n"),
+            Err(CorridorTableError::Csv { line: 1, .. })
+        ));
+    }
+}
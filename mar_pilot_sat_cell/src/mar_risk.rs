@@ -0,0 +1,109 @@
+//! Phoenix-class MAR risk state and its `ResidualKernel`. [file:7][file:14]
+//!
+//! The five MAR metrics (SAT loading, PFAS, pharmaceuticals, temperature,
+//! surcharge) are normalized through `SatCorridorTable`, so a zero-width band
+//! is rejected when the table is built and gold lands in r-space through the
+//! same checked `normalize` as the reading.
+
+use response_shard::sensitivity::{InputSpec, ResidualKernel};
+use response_shard::Residual;
+
+use crate::corridors::{CorridorTableError, SatCorridorTable};
+
+/// MAR corridor ids, in `compute_mar_risk_state` and `inputs()` order.
+pub const MAR_VAR_IDS: [&str; 5] = ["rSAT", "rPFAS", "rPHARMA", "rTEMP", "rSURCH"];
+
+/// Phoenix MAR risk bands; the one copy of this table. [file:14]
+pub const MAR_RISK_ALN: &str = include_str!("../../aln/particles/mar.satcell.risk.phoenix2026.aln");
+
+/// MAR SAT bands from `MAR_RISK_ALN`; placeholders until pilot data tightens them.
+pub fn mar_default_corridors() -> SatCorridorTable {
+    SatCorridorTable::from_aln(MAR_RISK_ALN).expect("MAR risk particle is consistent")
+}
+
+/// Normalize raw MAR metrics, in `MAR_VAR_IDS` order, into a residual.
+pub fn compute_mar_risk_state(table: &SatCorridorTable, raw: &[f64; 5]) -> Result<Residual, CorridorTableError> {
+    let readings: Vec<(&str, f64)> = MAR_VAR_IDS.iter().copied().zip(raw.iter().copied()).collect();
+    let coords = table.risk_coords(&readings)?.into_iter().map(|c| c.coord).collect();
+    Ok(Residual::from_coords(coords))
+}
+
+/// `compute_mar_risk_state` over the five raw MAR metrics. Default ranges
+/// span each row from safe to hard, widened to include the nominal reading.
+#[derive(Debug, Clone)]
+pub struct MarRiskKernel {
+    pub corridors: SatCorridorTable,
+    pub nominal: [f64; 5],
+}
+
+impl MarRiskKernel {
+    pub fn new(corridors: SatCorridorTable, nominal: [f64; 5]) -> Self {
+        Self { corridors, nominal }
+    }
+}
+
+impl ResidualKernel for MarRiskKernel {
+    type Error = CorridorTableError;
+
+    fn inputs(&self) -> Vec<InputSpec> {
+        MAR_VAR_IDS
+            .iter()
+            .zip(self.nominal)
+            .map(|(id, raw)| {
+                let (units, low, high) = self
+                    .corridors
+                    .get(id)
+                    .map_or((String::new(), raw, raw), |c| (c.units.clone(), c.safe, c.hard));
+                InputSpec::new(*id, units, raw, low.min(raw), high.max(raw))
+            })
+            .collect()
+    }
+
+    fn residual(&self, x: &[f64]) -> Result<Residual, CorridorTableError> {
+        compute_mar_risk_state(&self.corridors, &[x[0], x[1], x[2], x[3], x[4]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use response_shard::sensitivity::SensitivityAnalysis;
+
+    #[test]
+    fn partials_follow_the_mar_bands() {
+        let kernel = MarRiskKernel::new(mar_default_corridors(), [0.5, 0.3, 0.1, 0.4, 0.2]);
+        let report = SensitivityAnalysis::default().analyze(&kernel).unwrap();
+
+        // rSAT = (x - 0.30) / 0.65 with weight 0.30.
+        let sat = report.partial("rSAT").unwrap();
+        assert!((sat.d_coords["rSAT"] - 1.0 / 0.65).abs() < 1e-9);
+        assert!((sat.d_vt - 0.30 / 0.65).abs() < 1e-9);
+        // rPHARMA sits below its safe band.
+        assert_eq!(report.partial("rPHARMA").unwrap().d_vt, 0.0);
+        assert!(report.is_clamped("rPHARMA"));
+
+        let state = kernel.residual(&kernel.nominal).unwrap();
+        let gold = state.coords.iter().find(|c| c.var_id == "rSAT").unwrap().gold;
+        assert!((gold - 0.30 / 0.65).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_width_band_is_rejected_not_divided() {
+        let table = mar_default_corridors();
+        let ids: Vec<&str> = table.rows().iter().map(|r| r.var_id.as_str()).collect();
+        assert_eq!(ids, MAR_VAR_IDS);
+        let mut rows = table.rows().to_vec();
+        rows[4].safe = rows[4].hard;
+        rows[4].gold = rows[4].hard;
+        assert!(matches!(
+            SatCorridorTable::new(rows),
+            Err(CorridorTableError::ZeroWidthBand { var_id, .. }) if var_id == "rSURCH"
+        ));
+
+        let partial = SatCorridorTable::phoenix_default();
+        assert!(matches!(
+            compute_mar_risk_state(&partial, &[0.5; 5]),
+            Err(CorridorTableError::UnknownVar(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+//! `MarShard`: one Phoenix-class MAR cell with its bands and stored r_x. [file:14]
+//!
+//! The one definition of the MAR shard grammar; the loose `src/` tree
+//! re-exports these types. `mar_shard_csv` reads and writes it.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorridorBands {
+    pub var_id: String,    // e.g. "HLR", "PFAS", "TEMP"
+    pub units: String,     // "m/d", "ng/L", "degC"
+    pub safe: f64,         // inner "comfortable" band
+    pub gold: f64,         // regulatory / science "gold" limit
+    pub hard: f64,         // absolute never-exceed limit
+    pub weight_w: f64,     // weight in V(t)
+    pub lyap_channel: u16, // channel index for residual decomposition
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RiskCoord {
+    pub value: f64, // normalized r_x in [0,1]
+    pub bands: CorridorBands,
+    pub sigma: f64, // uncertainty
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KerScore {
+    pub knowledge_k: f64,  // 0–1
+    pub eco_impact_e: f64, // 0–1
+    pub risk_r: f64,       // 0–1
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarShard {
+    pub mar_id: String,
+    pub basin_id: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub aquifer_unit: String,
+    pub climate_class: String,
+
+    // Hydraulics
+    pub hlr_current: f64,
+    pub hlr_bands: CorridorBands, // var_id="HLR"
+    pub q_in_m3d: f64,
+    pub q_out_m3d: f64,
+    pub res_time_d: f64,
+    pub surcharge_count: u64,
+    pub r_surcharge: RiskCoord,
+
+    // CECs and nutrients
+    pub c_pfas_in_ngl: f64,
+    pub c_pfas_out_ngl: f64,
+    pub r_pfas: RiskCoord,
+
+    pub c_pharma_in_ngl: f64,
+    pub c_pharma_out_ngl: f64,
+    pub r_pharma: RiskCoord,
+
+    pub c_n_in_mgl: f64,
+    pub c_n_out_mgl: f64,
+    pub r_n: RiskCoord,
+
+    pub c_p_in_mgl: f64,
+    pub c_p_out_mgl: f64,
+    pub r_p: RiskCoord,
+
+    // Thermal and redox
+    pub t_plume_c: f64,
+    pub t_bands: CorridorBands, // var_id="TEMP"
+    pub r_thermal: RiskCoord,
+    pub redox_state_mv: f64,
+    pub r_redox: RiskCoord,
+
+    // Fouling
+    pub fouling_index: f64,
+    pub fouling_bands: CorridorBands, // var_id="FOUL"
+    pub cleaning_dose_eq: f64,
+    pub r_foul: RiskCoord,
+
+    // KER scores
+    pub ker: KerScore,
+}
+
+/// Normalize a raw metric into r_x in [0,1] using corridor bands.
+/// A NaN reading falls through to NaN, which no tolerance check accepts.
+pub fn normalize_metric(x: f64, bands: &CorridorBands) -> f64 {
+    if x <= bands.safe {
+        0.0
+    } else if x >= bands.hard {
+        1.0
+    } else {
+        (x - bands.safe) / (bands.hard - bands.safe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_metric_is_linear_between_safe_and_hard() {
+        let bands = CorridorBands {
+            var_id: "HLR".into(),
+            units: "m/d".into(),
+            safe: 0.25,
+            gold: 0.50,
+            hard: 0.70,
+            weight_w: 0.20,
+            lyap_channel: 0,
+        };
+        assert_eq!(normalize_metric(0.2, &bands), 0.0);
+        assert!((normalize_metric(0.35, &bands) - 0.1 / 0.45).abs() < 1e-12);
+        assert_eq!(normalize_metric(0.9, &bands), 1.0);
+        assert!(normalize_metric(f64::NAN, &bands).is_nan());
+    }
+}
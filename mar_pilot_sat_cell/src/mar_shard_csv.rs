@@ -0,0 +1,426 @@
+//! CSV reader, writer and r_x verify pass for the MAR SAT particle file. [file:14]
+//!
+//! `particles_MAR_SAT_Phoenix2026v1.csv` has one column per `MarShard` field
+//! plus the band triplets, and reads into the `mar_shard` types. Verify
+//! recomputes each r_x with `normalize_metric` where the file's own values
+//! show that is how the column was derived; see `RX_KERNELS`.
+//!
+//! The writer emits the same header but is not byte-exact. The CSV has no
+//! column for CEC/nutrient safe bands, for any weight but HLR's, or for
+//! sigma, so those are written as nothing and re-read as `UNLISTED_SAFE`,
+//! `UNLISTED_WEIGHT_W` and 0. Thermal and fouling bands are written from
+//! `t_bands` / `fouling_bands`, and `r_thermal` / `r_foul` re-read with the
+//! same bands. Numbers are written in shortest round-trip form (`0.50` →
+//! `0.5`), so values survive read → write → read exactly even where the text
+//! changes. Text fields are written unquoted, so the writer rejects any that
+//! would not read back as the same cell.
+
+use thiserror::Error;
+
+use crate::mar_shard::{normalize_metric, CorridorBands, KerScore, MarShard, RiskCoord};
+
+/// Header of particles_MAR_SAT_Phoenix2026v1.csv, in column order.
+pub const MAR_SAT_CSV_HEADER: [&str; 56] = [
+    "mar_id", "basin_id", "lat", "lon", "aquifer_unit", "climate_class",
+    "hlr_current_m_per_d", "hlr_safe", "hlr_gold", "hlr_hard", "hlr_weight_w",
+    "q_in_m3_per_d", "q_out_m3_per_d", "res_time_d", "surcharge_count", "r_surcharge",
+    "c_pfas_in_ngL", "c_pfas_out_ngL", "c_pfas_gold_ngL", "c_pfas_hard_ngL", "r_pfas",
+    "c_pharma_in_ngL", "c_pharma_out_ngL", "c_pharma_gold_ngL", "c_pharma_hard_ngL", "r_pharma",
+    "c_n_in_mgL", "c_n_out_mgL", "c_n_gold_mgL", "c_n_hard_mgL", "r_n",
+    "c_p_in_mgL", "c_p_out_mgL", "c_p_gold_mgL", "c_p_hard_mgL", "r_p",
+    "t_plume_C", "t_safe_C", "t_gold_C", "t_hard_C", "r_thermal",
+    "redox_state_mV", "redox_safe_mV", "redox_gold_mV", "redox_hard_mV", "r_redox",
+    "fouling_index", "foul_safe", "foul_gold", "foul_hard", "r_foul",
+    "cleaning_dose_eq", "ker_knowledge_k", "ker_ecoimpact_e", "ker_risk_r", "hex_provenance",
+];
+
+/// Stored r_x may differ from the kernel by this much (values are written to 2 dp).
+pub const RX_TOLERANCE: f64 = 0.01;
+
+/// Safe band given to CEC and nutrient rows, whose CSV columns carry only gold and hard.
+pub const UNLISTED_SAFE: f64 = 0.0;
+
+/// Weight given to every row but HLR, the only one with a weight column.
+pub const UNLISTED_WEIGHT_W: f64 = 0.0;
+
+/// How a stored r_x column follows from its row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RxKernel {
+    /// `normalize_metric` of the raw value against the column's bands.
+    Linear,
+    /// Published but not reproducible from the row; carried, not verified.
+    Unverified,
+}
+
+/// Per-column kernels, decided from the Phoenix 2026 file. Surcharge is
+/// linear in HLR between safe and hard (0.35 in 0.25–0.70 → 0.22). CEC and
+/// nutrient columns are `c_out / hard` with safe 0 (PFAS 3.9/70 → 0.05,
+/// N 3/25 → 0.12, P 0.5/5 → 0.10). Thermal, redox and fouling match no
+/// linear or squared map over their bands in either direction (24.8 °C in
+/// 22.5/25/27 is published as 0.15), so they are not checked. The position
+/// of each column is the `lyap_channel` its bands are read with.
+pub const RX_KERNELS: [(&str, RxKernel); 8] = [
+    ("r_surcharge", RxKernel::Linear),
+    ("r_pfas", RxKernel::Linear),
+    ("r_pharma", RxKernel::Linear),
+    ("r_n", RxKernel::Linear),
+    ("r_p", RxKernel::Linear),
+    ("r_thermal", RxKernel::Unverified),
+    ("r_redox", RxKernel::Unverified),
+    ("r_foul", RxKernel::Unverified),
+];
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum MarCsvError {
+    #[error("missing header row")]
+    MissingHeader,
+    #[error("header column {}: expected `{expected}`, found `{found}`", .column + 1)]
+    Header { column: usize, expected: &'static str, found: String },
+    #[error("line {line}: expected {expected} columns, found {found}")]
+    Columns { line: usize, expected: usize, found: usize },
+    #[error("line {line}: `{column}` = `{value}` does not parse")]
+    Field { line: usize, column: &'static str, value: String },
+    #[error("`{mar_id}`: `{column}` = `{value}` cannot be written as an unquoted CSV cell")]
+    Unwritable { mar_id: String, column: &'static str, value: String },
+}
+
+/// One CSV row: the shard plus the provenance stamp it was published with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarShardRow {
+    pub line: usize,
+    pub shard: MarShard,
+    pub hex_provenance: String,
+}
+
+/// A stored r_x that disagrees with `normalize_metric` over the row's raw value and bands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RxMismatch {
+    pub line: usize,
+    pub mar_id: String,
+    pub column: &'static str,
+    pub stored: f64,
+    pub recomputed: f64,
+}
+
+fn bands(var_id: &str, units: &str, safe: f64, gold: f64, hard: f64, weight_w: f64, lyap_channel: u16) -> CorridorBands {
+    CorridorBands {
+        var_id: var_id.into(),
+        units: units.into(),
+        safe,
+        gold,
+        hard,
+        weight_w,
+        lyap_channel,
+    }
+}
+
+fn stored(value: f64, bands: CorridorBands) -> RiskCoord {
+    RiskCoord { value, bands, sigma: 0.0 }
+}
+
+/// Cells of one row, parsed in header order.
+struct Cells<'a> {
+    line: usize,
+    cells: Vec<&'a str>,
+    next: usize,
+}
+
+impl Cells<'_> {
+    fn text(&mut self) -> String {
+        let cell = self.cells[self.next];
+        self.next += 1;
+        cell.to_string()
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self) -> Result<T, MarCsvError> {
+        let (column, cell) = (MAR_SAT_CSV_HEADER[self.next], self.cells[self.next]);
+        self.next += 1;
+        cell.parse().map_err(|_| MarCsvError::Field {
+            line: self.line,
+            column,
+            value: cell.to_string(),
+        })
+    }
+
+    fn num(&mut self) -> Result<f64, MarCsvError> {
+        self.parse()
+    }
+}
+
+fn parse_row(line: usize, text: &str) -> Result<MarShardRow, MarCsvError> {
+    let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+    if cells.len() != MAR_SAT_CSV_HEADER.len() {
+        return Err(MarCsvError::Columns {
+            line,
+            expected: MAR_SAT_CSV_HEADER.len(),
+            found: cells.len(),
+        });
+    }
+    let mut c = Cells { line, cells, next: 0 };
+
+    let (mar_id, basin_id) = (c.text(), c.text());
+    let (lat, lon) = (c.num()?, c.num()?);
+    let (aquifer_unit, climate_class) = (c.text(), c.text());
+
+    let hlr_current = c.num()?;
+    let (hlr_safe, hlr_gold, hlr_hard, hlr_w) = (c.num()?, c.num()?, c.num()?, c.num()?);
+    let hlr_bands = bands("HLR", "m/d", hlr_safe, hlr_gold, hlr_hard, hlr_w, 0);
+    let (q_in_m3d, q_out_m3d, res_time_d) = (c.num()?, c.num()?, c.num()?);
+    let surcharge_count: u64 = c.parse()?;
+    // Surcharge risk is HLR against the HLR bands.
+    let r_surcharge = stored(c.num()?, CorridorBands { var_id: "SURCHARGE".into(), ..hlr_bands.clone() });
+
+    // in, out, gold, hard, r_x
+    let mut cec = |var_id: &str, units: &str, channel: u16| -> Result<(f64, f64, RiskCoord), MarCsvError> {
+        let (c_in, c_out, gold, hard, value) = (c.num()?, c.num()?, c.num()?, c.num()?, c.num()?);
+        let b = bands(var_id, units, UNLISTED_SAFE, gold, hard, UNLISTED_WEIGHT_W, channel);
+        Ok((c_in, c_out, stored(value, b)))
+    };
+    let (c_pfas_in_ngl, c_pfas_out_ngl, r_pfas) = cec("PFAS", "ng/L", 1)?;
+    let (c_pharma_in_ngl, c_pharma_out_ngl, r_pharma) = cec("PHARMA", "ng/L", 2)?;
+    let (c_n_in_mgl, c_n_out_mgl, r_n) = cec("N", "mg/L", 3)?;
+    let (c_p_in_mgl, c_p_out_mgl, r_p) = cec("P", "mg/L", 4)?;
+
+    // value, safe, gold, hard, r_x
+    let mut banded = |var_id: &str, units: &str, channel: u16| -> Result<(f64, RiskCoord), MarCsvError> {
+        let (raw, safe, gold, hard, value) = (c.num()?, c.num()?, c.num()?, c.num()?, c.num()?);
+        let b = bands(var_id, units, safe, gold, hard, UNLISTED_WEIGHT_W, channel);
+        Ok((raw, stored(value, b)))
+    };
+    let (t_plume_c, r_thermal) = banded("TEMP", "degC", 5)?;
+    let (redox_state_mv, r_redox) = banded("REDOX", "mV", 6)?;
+    let (fouling_index, r_foul) = banded("FOUL", "dimensionless", 7)?;
+
+    let cleaning_dose_eq = c.num()?;
+    let ker = KerScore {
+        knowledge_k: c.num()?,
+        eco_impact_e: c.num()?,
+        risk_r: c.num()?,
+    };
+    let hex_provenance = c.text();
+
+    let shard = MarShard {
+        mar_id,
+        basin_id,
+        lat,
+        lon,
+        aquifer_unit,
+        climate_class,
+        hlr_current,
+        hlr_bands,
+        q_in_m3d,
+        q_out_m3d,
+        res_time_d,
+        surcharge_count,
+        r_surcharge,
+        c_pfas_in_ngl,
+        c_pfas_out_ngl,
+        r_pfas,
+        c_pharma_in_ngl,
+        c_pharma_out_ngl,
+        r_pharma,
+        c_n_in_mgl,
+        c_n_out_mgl,
+        r_n,
+        c_p_in_mgl,
+        c_p_out_mgl,
+        r_p,
+        t_plume_c,
+        t_bands: r_thermal.bands.clone(),
+        r_thermal,
+        redox_state_mv,
+        r_redox,
+        fouling_index,
+        fouling_bands: r_foul.bands.clone(),
+        cleaning_dose_eq,
+        r_foul,
+        ker,
+    };
+    Ok(MarShardRow { line, shard, hex_provenance })
+}
+
+/// Read every row of a MAR SAT particle CSV; the header must match `MAR_SAT_CSV_HEADER` exactly.
+pub fn read_mar_shards(input: &str) -> Result<Vec<MarShardRow>, MarCsvError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+    let (_, header) = lines.next().ok_or(MarCsvError::MissingHeader)?;
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    for (column, expected) in MAR_SAT_CSV_HEADER.iter().enumerate() {
+        let found = header.get(column).copied().unwrap_or("");
+        if found != *expected {
+            return Err(MarCsvError::Header { column, expected, found: found.to_string() });
+        }
+    }
+    if header.len() != MAR_SAT_CSV_HEADER.len() {
+        return Err(MarCsvError::Header {
+            column: MAR_SAT_CSV_HEADER.len(),
+            expected: "",
+            found: header[MAR_SAT_CSV_HEADER.len()].to_string(),
+        });
+    }
+
+    lines.map(|(line, text)| parse_row(line, text)).collect()
+}
+
+/// A text field as one unquoted cell, or `Unwritable` if the reader would not
+/// get it back: a comma or line break splits it, edge whitespace is trimmed,
+/// and a leading `#` in the first column turns the row into a comment.
+fn text_cell(mar_id: &str, column: &'static str, value: &str) -> Result<String, MarCsvError> {
+    let splits = value.contains([',', '\n', '\r']);
+    let comment = column == MAR_SAT_CSV_HEADER[0] && value.starts_with('#');
+    if splits || comment || value.trim() != value {
+        return Err(MarCsvError::Unwritable {
+            mar_id: mar_id.to_string(),
+            column,
+            value: value.to_string(),
+        });
+    }
+    Ok(value.to_string())
+}
+
+/// Write rows under the same header; see the module doc for what the CSV cannot carry.
+pub fn write_mar_shards(rows: &[MarShardRow]) -> Result<String, MarCsvError> {
+    let mut out = MAR_SAT_CSV_HEADER.join(",");
+    out.push('\n');
+    for row in rows {
+        let s = &row.shard;
+        let text = |column, value: &str| text_cell(&s.mar_id, column, value);
+        let cec = |c_in: f64, c_out: f64, r: &RiskCoord| {
+            format!("{},{},{},{},{}", c_in, c_out, r.bands.gold, r.bands.hard, r.value)
+        };
+        let banded = |raw: f64, b: &CorridorBands, r: &RiskCoord| {
+            format!("{},{},{},{},{}", raw, b.safe, b.gold, b.hard, r.value)
+        };
+        let h = &s.hlr_bands;
+        let cells = [
+            text("mar_id", &s.mar_id)?,
+            text("basin_id", &s.basin_id)?,
+            s.lat.to_string(),
+            s.lon.to_string(),
+            text("aquifer_unit", &s.aquifer_unit)?,
+            text("climate_class", &s.climate_class)?,
+            s.hlr_current.to_string(),
+            format!("{},{},{},{}", h.safe, h.gold, h.hard, h.weight_w),
+            format!("{},{},{},{}", s.q_in_m3d, s.q_out_m3d, s.res_time_d, s.surcharge_count),
+            s.r_surcharge.value.to_string(),
+            cec(s.c_pfas_in_ngl, s.c_pfas_out_ngl, &s.r_pfas),
+            cec(s.c_pharma_in_ngl, s.c_pharma_out_ngl, &s.r_pharma),
+            cec(s.c_n_in_mgl, s.c_n_out_mgl, &s.r_n),
+            cec(s.c_p_in_mgl, s.c_p_out_mgl, &s.r_p),
+            banded(s.t_plume_c, &s.t_bands, &s.r_thermal),
+            banded(s.redox_state_mv, &s.r_redox.bands, &s.r_redox),
+            banded(s.fouling_index, &s.fouling_bands, &s.r_foul),
+            s.cleaning_dose_eq.to_string(),
+            format!("{},{},{}", s.ker.knowledge_k, s.ker.eco_impact_e, s.ker.risk_r),
+            text("hex_provenance", &row.hex_provenance)?,
+        ];
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Recompute every `RxKernel::Linear` r_* from its raw value and bands; report
+/// those off by more than `tolerance`. `Unverified` columns are never reported.
+pub fn verify_mar_shards(rows: &[MarShardRow], tolerance: f64) -> Vec<RxMismatch> {
+    let mut out = Vec::new();
+    for row in rows {
+        let s = &row.shard;
+        let checks: [(f64, &RiskCoord); 8] = [
+            (s.hlr_current, &s.r_surcharge),
+            (s.c_pfas_out_ngl, &s.r_pfas),
+            (s.c_pharma_out_ngl, &s.r_pharma),
+            (s.c_n_out_mgl, &s.r_n),
+            (s.c_p_out_mgl, &s.r_p),
+            (s.t_plume_c, &s.r_thermal),
+            (s.redox_state_mv, &s.r_redox),
+            (s.fouling_index, &s.r_foul),
+        ];
+        for ((column, kernel), (raw, stored)) in RX_KERNELS.into_iter().zip(checks) {
+            if kernel == RxKernel::Unverified {
+                continue;
+            }
+            let recomputed = normalize_metric(raw, &stored.bands);
+            // A NaN stored value counts as a mismatch.
+            let agrees = (recomputed - stored.value).abs() <= tolerance;
+            if !agrees {
+                out.push(RxMismatch {
+                    line: row.line,
+                    mar_id: s.mar_id.clone(),
+                    column,
+                    stored: stored.value,
+                    recomputed,
+                });
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHOENIX_CSV: &str = include_str!("../../qpudatashards/particles_MAR_SAT_Phoenix2026v1.csv");
+
+    #[test]
+    fn reads_the_phoenix_particle_file_and_round_trips() {
+        let rows = read_mar_shards(PHOENIX_CSV).unwrap();
+        assert_eq!(rows.len(), 1);
+        let s = &rows[0].shard;
+        assert_eq!(s.mar_id, "MAR-PHX-SAT-01");
+        assert_eq!(s.hlr_bands.weight_w, 0.20);
+        assert_eq!(s.r_pfas.bands.hard, 70.0);
+        assert_eq!(s.r_redox.bands.safe, -150.0);
+        assert_eq!((s.t_bands.gold, s.fouling_bands.hard), (25.0, 1.0));
+        assert_eq!(s.ker.knowledge_k, 0.96);
+
+        let written = write_mar_shards(&rows).unwrap();
+        assert_eq!(written.lines().next().unwrap(), MAR_SAT_CSV_HEADER.join(","));
+        assert_eq!(read_mar_shards(&written).unwrap(), rows);
+        // Shortest form: the text changes, the values do not.
+        assert!(written.contains(",0.5,0.7,0.2,"));
+        assert_eq!(write_mar_shards(&read_mar_shards(&written).unwrap()).unwrap(), written);
+    }
+
+    #[test]
+    fn writer_rejects_text_that_would_not_read_back() {
+        let mut rows = read_mar_shards(PHOENIX_CSV).unwrap();
+        rows[0].shard.basin_id = "PHX,BASIN-1".into();
+        assert!(matches!(
+            write_mar_shards(&rows),
+            Err(MarCsvError::Unwritable { column: "basin_id", .. })
+        ));
+        rows[0].shard.basin_id = "PHX-BASIN-1".into();
+        rows[0].hex_provenance = "0xab\n".into();
+        assert!(matches!(
+            write_mar_shards(&rows),
+            Err(MarCsvError::Unwritable { column: "hex_provenance", .. })
+        ));
+    }
+
+    #[test]
+    fn verify_flags_a_tampered_rx() {
+        let mut rows = read_mar_shards(PHOENIX_CSV).unwrap();
+        // r_pharma is a real discrepancy in the published row: the c_out / hard
+        // kernel that reproduces r_pfas, r_n and r_p gives 10 / 80 = 0.125, not 0.08.
+        let published = verify_mar_shards(&rows, RX_TOLERANCE);
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].column, "r_pharma");
+        assert!((published[0].recomputed - 0.125).abs() < 1e-12);
+
+        rows[0].shard.r_n.value = 0.50;
+        // Unverified columns are carried as published.
+        rows[0].shard.r_thermal.value = 0.90;
+        let flagged = verify_mar_shards(&rows, RX_TOLERANCE);
+        let columns: Vec<&str> = flagged.iter().map(|m| m.column).collect();
+        assert_eq!(columns, ["r_pharma", "r_n"]);
+        let r_n = &flagged[1];
+        assert_eq!((r_n.line, r_n.mar_id.as_str(), r_n.stored), (2, "MAR-PHX-SAT-01", 0.50));
+        assert!((r_n.recomputed - 3.0 / 25.0).abs() < 1e-12);
+    }
+}
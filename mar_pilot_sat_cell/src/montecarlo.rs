@@ -0,0 +1,456 @@
+//! Monte Carlo propagation of input uncertainty through `evaluate_sat`. [file:6][file:14]
+//!
+//! Each uncertain input carries a distribution; every draw perturbs a base
+//! scenario and is evaluated like a point scenario. The RNG is seeded, so a
+//! report is reproducible from its seed. Draws are never truncated: a draw
+//! that fails input validation (a negative PFAS reading, c_out > c_in) is
+//! counted as invalid under `ValidationMode::Strict`, or repaired toward the
+//! unsafe side and counted as clamped under `ClampAndWarn`. Output
+//! distributions and probabilities cover the evaluated draws only.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::{Distribution, LogNormal, Normal};
+use response_shard::ResponseShard;
+use thiserror::Error;
+
+use crate::corridors::SatCorridorTable;
+use crate::validation::ValidationMode;
+use crate::{evaluate_sat_with_mode, SatEvalError, SatScenario};
+
+#[derive(Debug, Error)]
+pub enum MonteCarloError {
+    #[error("distribution for {input} is invalid: {reason}")]
+    InvalidDist { input: String, reason: String },
+    #[error("sample CSV line {line}: {reason}")]
+    Csv { line: usize, reason: String },
+    #[error("scenario has no contaminant `{0}`")]
+    UnknownContaminant(String),
+    #[error("sample count must be at least 1")]
+    NoSamples,
+    #[error("all {draws} draws failed input validation")]
+    AllDrawsInvalid { draws: usize },
+    #[error("draw {draw}: {source}")]
+    Eval {
+        draw: usize,
+        #[source]
+        source: SatEvalError,
+    },
+}
+
+/// A scenario input that can carry uncertainty.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UncertainInput {
+    Flow,
+    Hlr,
+    /// Effluent PFAS reading for the `r_pfas` corridor.
+    Pfas,
+    Temp,
+    ContaminantIn(String),
+    ContaminantOut(String),
+}
+
+impl fmt::Display for UncertainInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UncertainInput::Flow => write!(f, "flow_m3_d"),
+            UncertainInput::Hlr => write!(f, "hlr_m_d"),
+            UncertainInput::Pfas => write!(f, "pfas_ng_l"),
+            UncertainInput::Temp => write!(f, "temp_c"),
+            UncertainInput::ContaminantIn(name) => write!(f, "{}_in", name),
+            UncertainInput::ContaminantOut(name) => write!(f, "{}_out", name),
+        }
+    }
+}
+
+/// Distribution of one input, in that input's units.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputDist {
+    Normal { mean: f64, sd: f64 },
+    /// `mu`/`sigma` of ln(x).
+    LogNormal { mu: f64, sigma: f64 },
+    Uniform { low: f64, high: f64 },
+    /// Resampled with replacement, e.g. from repeated lab reports.
+    Empirical(Vec<f64>),
+}
+
+impl InputDist {
+    /// Lognormal with the given median and geometric standard deviation.
+    pub fn lognormal_median(median: f64, gsd: f64) -> Self {
+        InputDist::LogNormal {
+            mu: median.ln(),
+            sigma: gsd.ln(),
+        }
+    }
+
+    /// Empirical samples from `column` of a CSV with a header row.
+    pub fn from_csv(input: &str, column: &str) -> Result<Self, MonteCarloError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+        let (header_line, header) = lines.next().ok_or(MonteCarloError::Csv {
+            line: 1,
+            reason: "missing header".into(),
+        })?;
+        let col = header.split(',').map(str::trim).position(|h| h == column).ok_or(MonteCarloError::Csv {
+            line: header_line,
+            reason: format!("missing column `{}`", column),
+        })?;
+
+        let mut samples = Vec::new();
+        for (line, text) in lines {
+            let cell = text.split(',').map(str::trim).nth(col).ok_or(MonteCarloError::Csv {
+                line,
+                reason: format!("no cell for `{}`", column),
+            })?;
+            samples.push(cell.parse().map_err(|_| MonteCarloError::Csv {
+                line,
+                reason: format!("`{}` = `{}` is not a number", column, cell),
+            })?);
+        }
+        Ok(InputDist::Empirical(samples))
+    }
+
+    fn check(&self, input: &UncertainInput) -> Result<(), MonteCarloError> {
+        let reason = match self {
+            InputDist::Normal { mean, sd } if !(mean.is_finite() && sd.is_finite() && *sd >= 0.0) => {
+                Some(format!("normal({}, {})", mean, sd))
+            }
+            InputDist::LogNormal { mu, sigma } if !(mu.is_finite() && sigma.is_finite() && *sigma >= 0.0) => {
+                Some(format!("lognormal({}, {})", mu, sigma))
+            }
+            InputDist::Uniform { low, high } if !(low.is_finite() && high.is_finite() && low <= high) => {
+                Some(format!("uniform({}, {})", low, high))
+            }
+            InputDist::Empirical(samples) if samples.is_empty() => Some("no samples".into()),
+            InputDist::Empirical(samples) if !samples.iter().all(|s| s.is_finite()) => {
+                Some("non-finite sample".into())
+            }
+            _ => None,
+        };
+        match reason {
+            Some(reason) => Err(MonteCarloError::InvalidDist {
+                input: input.to_string(),
+                reason,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            InputDist::Normal { mean, sd } => Normal::new(*mean, *sd).expect("checked").sample(rng),
+            InputDist::LogNormal { mu, sigma } => LogNormal::new(*mu, *sigma).expect("checked").sample(rng),
+            InputDist::Uniform { low, high } if low == high => *low,
+            InputDist::Uniform { low, high } => rng.gen_range(*low..*high),
+            InputDist::Empirical(samples) => samples[rng.gen_range(0..samples.len())],
+        }
+    }
+}
+
+/// Sorted draws of one output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleDist {
+    sorted: Vec<f64>,
+}
+
+impl SampleDist {
+    fn new(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        Self { sorted: samples }
+    }
+
+    pub fn samples(&self) -> &[f64] {
+        &self.sorted
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sorted.iter().sum::<f64>() / self.sorted.len() as f64
+    }
+
+    pub fn sd(&self) -> f64 {
+        let m = self.mean();
+        let var = self.sorted.iter().map(|x| (x - m).powi(2)).sum::<f64>() / self.sorted.len() as f64;
+        var.sqrt()
+    }
+
+    /// Nearest-rank quantile, `p` in [0,1].
+    pub fn quantile(&self, p: f64) -> f64 {
+        let rank = (p.clamp(0.0, 1.0) * (self.sorted.len() - 1) as f64).round() as usize;
+        self.sorted[rank]
+    }
+
+    pub fn min(&self) -> f64 {
+        self.sorted[0]
+    }
+
+    pub fn max(&self) -> f64 {
+        self.sorted[self.sorted.len() - 1]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MonteCarloReport {
+    pub seed: u64,
+    pub draws: usize,
+    /// Draws that produced a shard; the distributions below cover these.
+    pub evaluated: usize,
+    /// Draws rejected by strict input validation.
+    pub invalid: usize,
+    /// Evaluated draws with inputs repaired under `ClampAndWarn`.
+    pub clamped: usize,
+    pub e: SampleDist,
+    pub vt: SampleDist,
+    /// Per `r_x`, keyed by `var_id`.
+    pub coords: BTreeMap<String, SampleDist>,
+    /// Share of draws with any r_x at or past its hard limit.
+    pub p_hard_breach: f64,
+    /// Share of draws whose shard `improves_over` the previous one; `None` without one.
+    pub p_improves: Option<f64>,
+}
+
+impl MonteCarloReport {
+    /// Share of all draws rejected as invalid.
+    pub fn p_invalid(&self) -> f64 {
+        self.invalid as f64 / self.draws as f64
+    }
+
+    /// Share of evaluated draws that needed clamping.
+    pub fn p_clamped(&self) -> f64 {
+        self.clamped as f64 / self.evaluated as f64
+    }
+}
+
+/// Seeded Monte Carlo over a base scenario.
+#[derive(Debug, Clone)]
+pub struct SatMonteCarlo {
+    pub base: SatScenario,
+    pub inputs: Vec<(UncertainInput, InputDist)>,
+    pub draws: usize,
+    pub seed: u64,
+    /// How draws failing input validation are handled; strict by default.
+    pub mode: ValidationMode,
+}
+
+impl SatMonteCarlo {
+    pub fn new(base: SatScenario, draws: usize, seed: u64) -> Self {
+        Self {
+            base,
+            inputs: Vec::new(),
+            draws,
+            seed,
+            mode: ValidationMode::Strict,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: ValidationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Give `input` a distribution; later calls for the same input replace it.
+    pub fn with_input(mut self, input: UncertainInput, dist: InputDist) -> Self {
+        self.inputs.retain(|(i, _)| *i != input);
+        self.inputs.push((input, dist));
+        self
+    }
+
+    fn apply(scenario: &mut SatScenario, input: &UncertainInput, value: f64) -> Result<(), MonteCarloError> {
+        let contaminant = |scenario: &mut SatScenario, name: &str| {
+            let found = scenario.contaminants.iter().position(|c| c.name == name);
+            found.ok_or_else(|| MonteCarloError::UnknownContaminant(name.to_string()))
+        };
+        match input {
+            UncertainInput::Flow => scenario.flow_m3_d = value,
+            UncertainInput::Hlr => scenario.hlr_m_d = value,
+            UncertainInput::Pfas => scenario.pfas_ng_l = value,
+            UncertainInput::Temp => scenario.temp_c = value,
+            UncertainInput::ContaminantIn(name) => {
+                let i = contaminant(scenario, name)?;
+                scenario.contaminants[i].c_in = value;
+            }
+            UncertainInput::ContaminantOut(name) => {
+                let i = contaminant(scenario, name)?;
+                scenario.contaminants[i].c_out = value;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn run(
+        &self,
+        corridors: &SatCorridorTable,
+        user_did: &str,
+        prev_shard: Option<&ResponseShard>,
+    ) -> Result<MonteCarloReport, MonteCarloError> {
+        if self.draws == 0 {
+            return Err(MonteCarloError::NoSamples);
+        }
+        for (input, dist) in &self.inputs {
+            dist.check(input)?;
+            Self::apply(&mut self.base.clone(), input, 0.0)?;
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let (mut e, mut vt) = (Vec::with_capacity(self.draws), Vec::with_capacity(self.draws));
+        let mut coords: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        let (mut hard, mut improves, mut invalid, mut clamped) = (0usize, 0usize, 0usize, 0usize);
+
+        for draw in 0..self.draws {
+            let mut scenario = self.base.clone();
+            for (input, dist) in &self.inputs {
+                Self::apply(&mut scenario, input, dist.sample(&mut rng))?;
+            }
+            let eval = match evaluate_sat_with_mode(corridors, user_did, &scenario, prev_shard, self.mode) {
+                Ok(eval) => eval,
+                Err(SatEvalError::Input(_)) => {
+                    invalid += 1;
+                    continue;
+                }
+                Err(source) => return Err(MonteCarloError::Eval { draw, source }),
+            };
+            if !eval.warnings.is_empty() {
+                clamped += 1;
+            }
+            let shard = &eval.shard;
+
+            e.push(shard.triad.eco_impact);
+            vt.push(shard.residual.vt);
+            for c in &shard.residual.coords {
+                coords.entry(c.var_id.clone()).or_default().push(c.value);
+            }
+            if shard.residual.coords.iter().any(|c| c.value >= c.hard) {
+                hard += 1;
+            }
+            if eval.improves {
+                improves += 1;
+            }
+        }
+
+        let evaluated = self.draws - invalid;
+        if evaluated == 0 {
+            return Err(MonteCarloError::AllDrawsInvalid { draws: self.draws });
+        }
+        let n = evaluated as f64;
+        Ok(MonteCarloReport {
+            seed: self.seed,
+            draws: self.draws,
+            evaluated,
+            invalid,
+            clamped,
+            e: SampleDist::new(e),
+            vt: SampleDist::new(vt),
+            coords: coords.into_iter().map(|(k, v)| (k, SampleDist::new(v))).collect(),
+            p_hard_breach: hard as f64 / n,
+            p_improves: prev_shard.map(|_| improves as f64 / n),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benefit::Contaminant;
+    use crate::evaluate_sat;
+
+    fn base() -> SatScenario {
+        SatScenario {
+            flow_m3_d: 20_000.0,
+            contaminants: vec![Contaminant::nitrate(12.0, 3.0)],
+            hlr_m_d: 0.1,
+            pfas_ng_l: 8.0,
+            temp_c: 22.0,
+            evidence: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn seeded_runs_repeat_and_point_inputs_have_no_spread() {
+        let table = SatCorridorTable::phoenix_default();
+        let mc = SatMonteCarlo::new(base(), 50, 7)
+            .with_input(UncertainInput::Temp, InputDist::Normal { mean: 22.0, sd: 1.5 })
+            .with_input(
+                UncertainInput::ContaminantOut("nitrate".into()),
+                InputDist::Uniform { low: 2.0, high: 4.0 },
+            );
+        let a = mc.run(&table, "bostrom18...", None).unwrap();
+        let b = mc.run(&table, "bostrom18...", None).unwrap();
+        assert_eq!(a.vt, b.vt);
+        assert_eq!(a.e, b.e);
+        assert!(a.coords["r_temp"].sd() > 0.0);
+        assert_eq!(a.coords["r_sat"].sd(), 0.0);
+        assert_eq!(a.p_improves, None);
+
+        let point = evaluate_sat(&table, "bostrom18...", &base(), None).unwrap();
+        let fixed = SatMonteCarlo::new(base(), 5, 7).run(&table, "bostrom18...", None).unwrap();
+        assert_eq!(fixed.vt.min(), point.shard.residual.vt);
+        assert_eq!(fixed.vt.max(), point.shard.residual.vt);
+    }
+
+    #[test]
+    fn lab_spread_gives_breach_and_tightening_probabilities() {
+        let table = SatCorridorTable::phoenix_default();
+        let earlier = SatScenario {
+            flow_m3_d: 15_000.0,
+            pfas_ng_l: 10.0,
+            ..base()
+        };
+        let prev = evaluate_sat(&table, "bostrom18...", &earlier, None).unwrap().shard;
+        let lab = "# PFAS lab reports, ng/L\nsample_id,pfas_ng_l\nA1,6.5\nA2,8.0\nA3,9.1\nA4,24.0\n";
+        let report = SatMonteCarlo::new(base(), 400, 42)
+            .with_input(UncertainInput::Pfas, InputDist::from_csv(lab, "pfas_ng_l").unwrap())
+            .run(&table, "bostrom18...", Some(&prev))
+            .unwrap();
+
+        // One report in four sits past the 20 ng/L hard limit.
+        assert!((report.p_hard_breach - 0.25).abs() < 0.07);
+        let p = report.p_improves.unwrap();
+        assert!((p - 0.75).abs() < 0.07);
+        assert_eq!(report.coords["r_pfas"].max(), 1.0);
+
+        assert!(matches!(
+            SatMonteCarlo::new(base(), 10, 1)
+                .with_input(
+                    UncertainInput::ContaminantIn("pfas".into()),
+                    InputDist::lognormal_median(7.0, 1.3),
+                )
+                .run(&table, "bostrom18...", None),
+            Err(MonteCarloError::UnknownContaminant(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_draws_are_counted_not_truncated() {
+        let table = SatCorridorTable::phoenix_default();
+        let mc = SatMonteCarlo::new(base(), 200, 3)
+            .with_input(UncertainInput::Pfas, InputDist::Normal { mean: 1.0, sd: 2.0 })
+            .with_input(
+                UncertainInput::ContaminantOut("nitrate".into()),
+                InputDist::Uniform { low: 2.0, high: 14.0 },
+            );
+
+        let strict = mc.run(&table, "bostrom18...", None).unwrap();
+        assert!(strict.invalid > 0 && strict.evaluated > 0);
+        assert_eq!(strict.invalid + strict.evaluated, 200);
+        assert_eq!(strict.clamped, 0);
+
+        let clamped = mc.clone().with_mode(ValidationMode::ClampAndWarn).run(&table, "bostrom18...", None).unwrap();
+        assert_eq!((clamped.invalid, clamped.evaluated), (0, 200));
+        assert!(clamped.p_clamped() > 0.0);
+        // A negative PFAS draw lands on the hard limit, never on the safe side.
+        assert!(clamped.p_hard_breach > 0.0);
+
+        let hopeless = SatMonteCarlo::new(base(), 5, 3)
+            .with_input(UncertainInput::Flow, InputDist::Uniform { low: -2.0, high: -1.0 });
+        assert!(matches!(
+            hopeless.run(&table, "bostrom18...", None),
+            Err(MonteCarloError::AllDrawsInvalid { draws: 5 })
+        ));
+    }
+}
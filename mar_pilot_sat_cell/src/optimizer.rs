@@ -0,0 +1,421 @@
+//! Operating-point search for the SAT pilot cell. [file:7][file:14]
+//!
+//! Grid search over declared actuator bounds (flow, HLR, wet fraction of the
+//! wet/dry cycle). A point is feasible when `safestep_with` against the
+//! current shard returns `Ok`: every coordinate within its gold band and V_t
+//! not rising. The feasible point with the highest E wins; ties go to the
+//! lower V_t. A point is scored on its cycle average: delivered flow is
+//! `flow · wet_fraction` and the plume sits `wet_fraction` of the way from
+//! ambient to influent temperature. Flow and HLR are coupled through the
+//! basin: wet-day flow may not exceed `hlr · area_m2 · (1 − fouling_index)`,
+//! so flow can only rise as far as the loading the corridors allow.
+
+use std::ops::RangeInclusive;
+
+use response_shard::aln_invariants::{safestep_with, BreachKind, CorridorDecision, SafeStepPolicy};
+use response_shard::ResponseShard;
+use thiserror::Error;
+
+use crate::corridors::SatCorridorTable;
+use crate::simulator::SatBasinConfig;
+use crate::{evaluate_sat, SatEvalError, SatEvaluation, SatScenario};
+
+/// Grid points per actuator unless set with `with_resolution`.
+pub const DEFAULT_RESOLUTION: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Actuator {
+    Flow,
+    Hlr,
+    WetFraction,
+}
+
+impl Actuator {
+    pub const ALL: [Actuator; 3] = [Actuator::Flow, Actuator::Hlr, Actuator::WetFraction];
+}
+
+#[derive(Debug, Error)]
+pub enum OptimizerError {
+    #[error("bounds for {actuator:?} are empty, negative or non-finite")]
+    InvalidBounds { actuator: Actuator },
+    #[error("fouling index must be in [0, 1), got {0}")]
+    InvalidFouling(f64),
+    #[error("resolution must be at least 2, got {0}")]
+    InvalidResolution(usize),
+    #[error("none of {evaluated} points passes safestep against the current shard")]
+    NoFeasiblePoint { evaluated: usize },
+    #[error("point could not be evaluated: {0}")]
+    Eval(#[from] SatEvalError),
+}
+
+/// Declared actuator limits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActuatorBounds {
+    pub flow_m3_d: RangeInclusive<f64>,
+    pub hlr_m_d: RangeInclusive<f64>,
+    /// Fraction of the wet/dry cycle spent wet, within [0,1].
+    pub wet_fraction: RangeInclusive<f64>,
+}
+
+impl ActuatorBounds {
+    pub fn range(&self, actuator: Actuator) -> &RangeInclusive<f64> {
+        match actuator {
+            Actuator::Flow => &self.flow_m3_d,
+            Actuator::Hlr => &self.hlr_m_d,
+            Actuator::WetFraction => &self.wet_fraction,
+        }
+    }
+
+    fn check(&self) -> Result<(), OptimizerError> {
+        for actuator in Actuator::ALL {
+            let r = self.range(actuator);
+            let ok = r.start().is_finite()
+                && r.end().is_finite()
+                && *r.start() >= 0.0
+                && r.start() <= r.end()
+                && (actuator != Actuator::WetFraction || *r.end() <= 1.0);
+            if !ok {
+                return Err(OptimizerError::InvalidBounds { actuator });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperatingPoint {
+    pub flow_m3_d: f64,
+    pub hlr_m_d: f64,
+    pub wet_fraction: f64,
+}
+
+impl OperatingPoint {
+    pub fn get(&self, actuator: Actuator) -> f64 {
+        match actuator {
+            Actuator::Flow => self.flow_m3_d,
+            Actuator::Hlr => self.hlr_m_d,
+            Actuator::WetFraction => self.wet_fraction,
+        }
+    }
+
+    fn with(mut self, actuator: Actuator, value: f64) -> Self {
+        match actuator {
+            Actuator::Flow => self.flow_m3_d = value,
+            Actuator::Hlr => self.hlr_m_d = value,
+            Actuator::WetFraction => self.wet_fraction = value,
+        }
+        self
+    }
+}
+
+/// What stops E from rising further at the optimum.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingConstraint {
+    /// The actuator sits on a declared bound and moving past it would raise E.
+    Bound { actuator: Actuator, at_max: bool },
+    /// Raising E moves `var_id` past its gold band.
+    Gold { var_id: String },
+    /// Raising E moves `var_id` past its hard limit.
+    Hard { var_id: String },
+    /// Raising E makes V_t rise over the current shard.
+    Lyapunov,
+    /// Raising E needs more flow than `hlr · area_m2 · (1 − fouling_index)` admits.
+    Area,
+}
+
+#[derive(Debug, Clone)]
+pub struct OptimumReport {
+    pub point: OperatingPoint,
+    pub evaluation: SatEvaluation,
+    pub binding: Vec<BindingConstraint>,
+    pub evaluated: usize,
+    pub feasible: usize,
+}
+
+/// Grid search for the operating point with the highest E that passes safestep.
+#[derive(Debug, Clone)]
+pub struct SatOptimizer {
+    pub basin: SatBasinConfig,
+    pub corridors: SatCorridorTable,
+    pub bounds: ActuatorBounds,
+    pub policy: SafeStepPolicy,
+    pub resolution: usize,
+    /// Current basin fouling; 0 is a clean floor.
+    pub fouling_index: f64,
+}
+
+struct Candidate {
+    point: OperatingPoint,
+    evaluation: SatEvaluation,
+    breaches: Vec<BindingConstraint>,
+}
+
+impl Candidate {
+    fn e(&self) -> f64 {
+        self.evaluation.shard.triad.eco_impact
+    }
+
+    fn vt(&self) -> f64 {
+        self.evaluation.shard.residual.vt
+    }
+}
+
+impl SatOptimizer {
+    pub fn new(basin: SatBasinConfig, corridors: SatCorridorTable, bounds: ActuatorBounds) -> Self {
+        Self {
+            basin,
+            corridors,
+            bounds,
+            policy: SafeStepPolicy::default(),
+            resolution: DEFAULT_RESOLUTION,
+            fouling_index: 0.0,
+        }
+    }
+
+    pub fn with_fouling_index(mut self, fouling_index: f64) -> Self {
+        self.fouling_index = fouling_index;
+        self
+    }
+
+    /// Wet-day flow the basin can take at `hlr_m_d`, m³/d.
+    pub fn capacity_m3_d(&self, hlr_m_d: f64) -> f64 {
+        hlr_m_d * self.basin.area_m2 * (1.0 - self.fouling_index)
+    }
+
+    pub fn with_resolution(mut self, resolution: usize) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: SafeStepPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Cycle-average scenario for `point`.
+    pub fn scenario(&self, point: &OperatingPoint) -> SatScenario {
+        let b = &self.basin;
+        SatScenario {
+            flow_m3_d: point.flow_m3_d * point.wet_fraction,
+            contaminants: b.contaminants.clone(),
+            hlr_m_d: point.hlr_m_d,
+            pfas_ng_l: b.pfas_out_ng_l,
+            temp_c: b.ambient_temp_c + point.wet_fraction * (b.influent_temp_c - b.ambient_temp_c),
+            evidence: b.evidence.clone(),
+        }
+    }
+
+    fn step(&self, actuator: Actuator) -> f64 {
+        let r = self.bounds.range(actuator);
+        (r.end() - r.start()) / (self.resolution - 1) as f64
+    }
+
+    fn grid(&self, actuator: Actuator) -> Vec<f64> {
+        let r = self.bounds.range(actuator);
+        if r.start() == r.end() {
+            return vec![*r.start()];
+        }
+        let step = self.step(actuator);
+        (0..self.resolution).map(|i| r.start() + step * i as f64).collect()
+    }
+
+    fn candidate(
+        &self,
+        user_did: &str,
+        point: OperatingPoint,
+        current: &ResponseShard,
+    ) -> Result<Candidate, OptimizerError> {
+        let evaluation = evaluate_sat(&self.corridors, user_did, &self.scenario(&point), Some(current))?;
+        let decision = safestep_with(&current.residual, &evaluation.shard.residual, &self.policy);
+        let mut breaches = decision
+            .breaches
+            .into_iter()
+            .map(|b| match b.kind {
+                BreachKind::Hard => BindingConstraint::Hard { var_id: b.var_id },
+                BreachKind::Gold => BindingConstraint::Gold { var_id: b.var_id },
+                BreachKind::Lyapunov => BindingConstraint::Lyapunov,
+            })
+            .collect::<Vec<_>>();
+        debug_assert_eq!(breaches.is_empty(), decision.decision == CorridorDecision::Ok);
+        if point.flow_m3_d > self.capacity_m3_d(point.hlr_m_d) {
+            breaches.push(BindingConstraint::Area);
+        }
+        Ok(Candidate { point, evaluation, breaches })
+    }
+
+    /// Search the grid against `current`, the shard the basin runs under now.
+    pub fn search(&self, user_did: &str, current: &ResponseShard) -> Result<OptimumReport, OptimizerError> {
+        self.bounds.check()?;
+        if !(0.0..1.0).contains(&self.fouling_index) {
+            return Err(OptimizerError::InvalidFouling(self.fouling_index));
+        }
+        if self.resolution < 2 {
+            return Err(OptimizerError::InvalidResolution(self.resolution));
+        }
+
+        let mut best: Option<Candidate> = None;
+        let (mut evaluated, mut feasible) = (0, 0);
+        for flow_m3_d in self.grid(Actuator::Flow) {
+            for hlr_m_d in self.grid(Actuator::Hlr) {
+                for wet_fraction in self.grid(Actuator::WetFraction) {
+                    let point = OperatingPoint { flow_m3_d, hlr_m_d, wet_fraction };
+                    let c = self.candidate(user_did, point, current)?;
+                    evaluated += 1;
+                    if !c.breaches.is_empty() {
+                        continue;
+                    }
+                    feasible += 1;
+                    let better = best
+                        .as_ref()
+                        .is_none_or(|b| c.e() > b.e() || (c.e() == b.e() && c.vt() < b.vt()));
+                    if better {
+                        best = Some(c);
+                    }
+                }
+            }
+        }
+        let best = best.ok_or(OptimizerError::NoFeasiblePoint { evaluated })?;
+        let binding = self.binding(user_did, &best, current)?;
+        Ok(OptimumReport {
+            point: best.point,
+            evaluation: best.evaluation,
+            binding,
+            evaluated,
+            feasible,
+        })
+    }
+
+    /// Probe one grid step each way per actuator, past the bounds too: a probe that
+    /// raises E names what blocks it, the bound it lies beyond and its breaches.
+    fn binding(
+        &self,
+        user_did: &str,
+        best: &Candidate,
+        current: &ResponseShard,
+    ) -> Result<Vec<BindingConstraint>, OptimizerError> {
+        let mut binding = Vec::new();
+        for actuator in Actuator::ALL {
+            let step = self.step(actuator);
+            if step == 0.0 {
+                continue;
+            }
+            let range = self.bounds.range(actuator);
+            for at_max in [false, true] {
+                let value = best.point.get(actuator) + if at_max { step } else { -step };
+                if value < 0.0 || (actuator == Actuator::WetFraction && value > 1.0) {
+                    continue;
+                }
+                let probe = self.candidate(user_did, best.point.with(actuator, value), current)?;
+                if probe.e() <= best.e() {
+                    continue;
+                }
+                let mut found = Vec::new();
+                if !range.contains(&value) {
+                    found.push(BindingConstraint::Bound { actuator, at_max });
+                }
+                // A feasible probe inside the bounds would have been picked by the grid.
+                debug_assert!(!found.is_empty() || !probe.breaches.is_empty());
+                found.extend(probe.breaches);
+                for c in found {
+                    if !binding.contains(&c) {
+                        binding.push(c);
+                    }
+                }
+            }
+        }
+        Ok(binding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current() -> ResponseShard {
+        let scenario = SatScenario {
+            flow_m3_d: 5_000.0,
+            contaminants: SatBasinConfig::phoenix_pilot().contaminants,
+            hlr_m_d: 0.14,
+            pfas_ng_l: 3.9,
+            temp_c: 24.0,
+            evidence: Vec::new(),
+        };
+        evaluate_sat(&SatCorridorTable::phoenix_default(), "bostrom18...", &scenario, None)
+            .unwrap()
+            .shard
+    }
+
+    fn bounds() -> ActuatorBounds {
+        ActuatorBounds {
+            flow_m3_d: 1_000.0..=20_000.0,
+            hlr_m_d: 0.05..=0.25,
+            wet_fraction: 0.2..=1.0,
+        }
+    }
+
+    #[test]
+    fn area_couples_flow_to_hlr() {
+        let optimizer =
+            SatOptimizer::new(SatBasinConfig::phoenix_pilot(), SatCorridorTable::phoenix_default(), bounds());
+        let report = optimizer.search("bostrom18...", &current()).unwrap();
+
+        // 50 000 m² at the highest HLR V_t allows (0.125 m/d) takes 6 250 m³/d.
+        assert_eq!(report.point.hlr_m_d, 0.125);
+        assert_eq!(report.point.flow_m3_d, 5_750.0);
+        assert!(report.point.flow_m3_d <= optimizer.capacity_m3_d(report.point.hlr_m_d));
+        assert_eq!(report.binding, vec![BindingConstraint::Area]);
+
+        let fouled = optimizer.clone().with_fouling_index(0.5);
+        let report = fouled.search("bostrom18...", &current()).unwrap();
+        assert_eq!(report.point.flow_m3_d, 1_000.0);
+        assert!(report.point.flow_m3_d <= fouled.capacity_m3_d(report.point.hlr_m_d));
+        assert!(matches!(
+            optimizer.with_fouling_index(1.0).search("bostrom18...", &current()),
+            Err(OptimizerError::InvalidFouling(_))
+        ));
+    }
+
+    #[test]
+    fn flow_bound_binds_on_a_large_basin() {
+        let mut basin = SatBasinConfig::phoenix_pilot();
+        basin.area_m2 = 200_000.0;
+        let optimizer = SatOptimizer::new(basin, SatCorridorTable::phoenix_default(), bounds());
+        let current = current();
+        let report = optimizer.search("bostrom18...", &current).unwrap();
+
+        assert_eq!(report.point.flow_m3_d, 20_000.0);
+        assert_eq!(report.point.wet_fraction, 1.0);
+        assert_eq!(report.point.hlr_m_d, 0.1);
+        assert_eq!(
+            report.binding,
+            vec![
+                BindingConstraint::Bound { actuator: Actuator::Flow, at_max: true },
+                BindingConstraint::Area
+            ]
+        );
+        assert!(report.evaluation.shard.triad.eco_impact > current.triad.eco_impact);
+        assert!(report.feasible < report.evaluated);
+    }
+
+    #[test]
+    fn hot_influent_binds_the_temperature_gold_band() {
+        let mut basin = SatBasinConfig::phoenix_pilot();
+        basin.influent_temp_c = 30.0;
+        let optimizer = SatOptimizer::new(basin, SatCorridorTable::phoenix_default(), bounds());
+        let report = optimizer.search("bostrom18...", &current()).unwrap();
+
+        // Plume 20 + 10·wet_fraction °C stays under the 25 °C gold band.
+        assert_eq!(report.point.wet_fraction, 0.5);
+        assert!(report.binding.contains(&BindingConstraint::Gold { var_id: "r_temp".into() }));
+
+        let tight = ActuatorBounds {
+            hlr_m_d: 0.2..=0.25,
+            ..bounds()
+        };
+        let strict = SatOptimizer::new(SatBasinConfig::phoenix_pilot(), SatCorridorTable::phoenix_default(), tight);
+        assert!(matches!(
+            strict.search("bostrom18...", &current()),
+            Err(OptimizerError::NoFeasiblePoint { .. })
+        ));
+    }
+}
@@ -1,119 +1,224 @@
-use response_shard::{RiskCoord, Residual, Triad, DraftAssessment, evaluate_draft};
+pub mod benefit;
+pub mod corridors;
+pub mod mar_risk;
+pub mod mar_shard;
+pub mod mar_shard_csv;
+pub mod montecarlo;
+pub mod optimizer;
+pub mod sensitivity;
+pub mod simulator;
+pub mod validation;
+
+use benefit::{BenefitError, Contaminant, MassBalance};
+use corridors::{CorridorTableError, SatCoord, SatCorridorTable};
+use response_shard::evidence::EvidenceRef;
+use response_shard::ker::{KerCalculator, KerError, KerReport};
+use response_shard::validation::ValidationErrors;
+use response_shard::{RiskCoord, DraftAssessment, ResponseShard, evaluate_draft};
 use thiserror::Error;
+use validation::{check_scenario, InputErrors, InputWarning, ValidationMode, INPUT_CLAMPED_TAG};
 
-/// Simple mass-balance kernel for SAT cell eco-benefit. [file:14]
+/// Simple mass-balance kernel for SAT cell eco-benefit: nitrate kg/d removed. [file:14]
+/// Clamps a reversed pair to 0. `evaluate_sat` does not use it: E there comes from
+/// `MassBalance` over every contaminant, after input validation.
 pub fn eco_benefit_kg_removed(nitrate_in_mg_l: f64, nitrate_out_mg_l: f64, flow_m3_d: f64) -> f64 {
     let delta_mg_l = (nitrate_in_mg_l - nitrate_out_mg_l).max(0.0);
-    let kg_per_m3 = delta_mg_l * 1e-6;
+    // 1 mg/L = 1 g/m3 = 1e-3 kg/m3
+    let kg_per_m3 = delta_mg_l * 1e-3;
     kg_per_m3 * flow_m3_d
 }
 
-/// Normalize a variable into r_x ∈ [0,1] given safe/gold/hard bands. [file:7]
-fn normalize(value: f64, safe: f64, gold: f64, hard: f64) -> f64 {
-    if value <= safe {
-        0.0
-    } else if value >= hard {
-        1.0
-    } else {
-        (value - safe) / (hard - safe)
-    }
-}
-
-/// Compute risk coordinates for a SAT scenario. [file:14]
+/// Compute risk coordinates for a SAT scenario with the Phoenix corridor table. [file:14]
 pub fn sat_risk_coords(
     hlr_m_d: f64,
     pfas_ng_l: f64,
     temp_c: f64,
 ) -> Vec<RiskCoord> {
-    let r_sat = normalize(hlr_m_d, 0.05, 0.15, 0.25);
-    let r_pfas = normalize(pfas_ng_l, 5.0, 10.0, 20.0);
-    let r_temp = normalize(temp_c, 15.0, 25.0, 30.0);
-
-    vec![
-        RiskCoord {
-            var_id: "r_sat".into(),
-            value: r_sat,
-            safe: 0.0,
-            gold: 0.7,
-            hard: 1.0,
-            weight: 0.4,
-        },
-        RiskCoord {
-            var_id: "r_pfas".into(),
-            value: r_pfas,
-            safe: 0.0,
-            gold: 0.7,
-            hard: 1.0,
-            weight: 0.4,
-        },
-        RiskCoord {
-            var_id: "r_temp".into(),
-            value: r_temp,
-            safe: 0.0,
-            gold: 0.7,
-            hard: 1.0,
-            weight: 0.2,
-        },
-    ]
+    sat_risk_coords_with(&SatCorridorTable::phoenix_default(), hlr_m_d, pfas_ng_l, temp_c)
+        .expect("Phoenix table defines every SAT field")
+        .into_iter()
+        .map(|c| c.coord)
+        .collect()
+}
+
+/// Compute risk coordinates against a site's corridor table, keeping physical bands. [file:14]
+pub fn sat_risk_coords_with(
+    table: &SatCorridorTable,
+    hlr_m_d: f64,
+    pfas_ng_l: f64,
+    temp_c: f64,
+) -> Result<Vec<SatCoord>, CorridorTableError> {
+    table.risk_coords(&[("r_sat", hlr_m_d), ("r_pfas", pfas_ng_l), ("r_temp", temp_c)])
 }
 
 #[derive(Debug, Error)]
 pub enum SatEvalError {
-    #[error("Previous shard missing or invalid")]
-    MissingPrevious,
+    #[error("Scenario inputs are invalid: {0}")]
+    Input(#[from] InputErrors),
+    #[error("Scenario produced an invalid shard: {0}")]
+    InvalidShard(#[from] ValidationErrors),
+    #[error("K/E/R could not be derived: {0}")]
+    Ker(#[from] KerError),
+    #[error("Corridor table does not cover the scenario: {0}")]
+    Corridor(#[from] CorridorTableError),
+    #[error("Mass balance could not be computed: {0}")]
+    Benefit(#[from] BenefitError),
 }
 
-/// Evaluate whether a proposed configuration tightens the SAT pilot shard. [file:14]
-pub fn evaluate_sat_scenario(
+/// Critical fields a SAT scenario must back with corridor coordinates. [file:14]
+pub const SAT_CRITICAL_FIELDS: [&str; 3] = ["r_sat", "r_pfas", "r_temp"];
+
+/// Benefit bounds B_min/B_max in hazard-weighted kg/d (nitrate weight 1). [file:14]
+pub const SAT_BENEFIT_MIN_KG_D: f64 = 0.0;
+pub const SAT_BENEFIT_MAX_KG_D: f64 = 1000.0;
+
+/// Inputs of one SAT evaluation. [file:14]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatScenario {
+    pub flow_m3_d: f64,
+    /// In/out concentrations feeding the benefit B.
+    pub contaminants: Vec<Contaminant>,
+    pub hlr_m_d: f64,
+    /// Effluent PFAS, normalized by the `r_pfas` corridor.
+    pub pfas_ng_l: f64,
+    pub temp_c: f64,
+    /// Caller-supplied evidence (corridor rows actually loaded, lab reports, shard refs).
+    /// K counts only the corridor rows here; the evaluator adds pilot readings, never rows.
+    pub evidence: Vec<EvidenceRef>,
+}
+
+/// Shard plus the mass balance and K/E/R breakdown behind it.
+#[derive(Debug, Clone)]
+pub struct SatEvaluation {
+    pub shard: ResponseShard,
+    /// `shard.improves_over(prev)`; false without a previous shard.
+    pub improves: bool,
+    pub mass_balance: MassBalance,
+    pub ker: KerReport,
+    /// Inputs replaced under `ValidationMode::ClampAndWarn`; empty otherwise.
+    pub warnings: Vec<InputWarning>,
+}
+
+/// Evaluate a scenario against a site's corridor table, rejecting bad inputs. [file:6][file:14]
+pub fn evaluate_sat(
+    corridors: &SatCorridorTable,
     user_did: &str,
-    nitrate_in_mg_l: f64,
-    nitrate_out_mg_l: f64,
-    flow_m3_d: f64,
-    hlr_m_d: f64,
-    pfas_ng_l: f64,
-    temp_c: f64,
-    prev_shard: Option<response_shard::ResponseShard>,
-) -> Result<(response_shard::ResponseShard, bool), SatEvalError> {
-    let benefit = eco_benefit_kg_removed(nitrate_in_mg_l, nitrate_out_mg_l, flow_m3_d);
-    let e = if benefit <= 0.0 {
-        0.0
-    } else if benefit >= 1000.0 {
-        1.0
-    } else {
-        benefit / 1000.0
-    };
+    scenario: &SatScenario,
+    prev_shard: Option<&ResponseShard>,
+) -> Result<SatEvaluation, SatEvalError> {
+    evaluate_sat_with_mode(corridors, user_did, scenario, prev_shard, ValidationMode::Strict)
+}
 
-    let coords = sat_risk_coords(hlr_m_d, pfas_ng_l, temp_c);
-    let residual = response_shard::Residual::from_coords(coords.clone());
+/// As `evaluate_sat`; `ValidationMode::ClampAndWarn` repairs bad inputs conservatively
+/// and tags the shard `input-clamped`.
+pub fn evaluate_sat_with_mode(
+    corridors: &SatCorridorTable,
+    user_did: &str,
+    scenario: &SatScenario,
+    prev_shard: Option<&ResponseShard>,
+    mode: ValidationMode,
+) -> Result<SatEvaluation, SatEvalError> {
+    let (checked, warnings) = check_scenario(scenario, corridors, mode)?;
+    let scenario = &checked;
+    let mass_balance = MassBalance::compute(scenario.flow_m3_d, &scenario.contaminants)?;
+    let benefit = mass_balance.kernel(SAT_BENEFIT_MIN_KG_D, SAT_BENEFIT_MAX_KG_D);
+    let sat_coords = sat_risk_coords_with(corridors, scenario.hlr_m_d, scenario.pfas_ng_l, scenario.temp_c)?;
 
-    // Simple illustrative K/R; in practice, use real corridor coverage and residual. [file:6][file:14]
-    let k = 0.93;
-    let r = residual.vt.min(0.3);
+    let mut evidence = scenario.evidence.clone();
+    for c in &scenario.contaminants {
+        let unit = c.unit.as_str();
+        evidence.push(EvidenceRef::pilot(format!("{}_in", c.name), c.c_in, unit));
+        evidence.push(EvidenceRef::pilot(format!("{}_out", c.name), c.c_out, unit));
+    }
+    evidence.push(EvidenceRef::pilot("flow", scenario.flow_m3_d, "m3/d"));
+    // Reading and physical bands per coordinate, since `RiskCoord` holds only r-space bands.
+    for c in &sat_coords {
+        let row = &c.corridor;
+        evidence.push(EvidenceRef::pilot(row.var_id.clone(), c.raw, row.units.clone()));
+        evidence.push(EvidenceRef::band(row.var_id.clone(), row.units.clone(), row.safe, row.gold, row.hard));
+    }
+    let coords: Vec<RiskCoord> = sat_coords.into_iter().map(|c| c.coord).collect();
 
-    let triad_inputs = response_shard::TriadInputs { k, e, r };
+    // K from the caller's corridor-row evidence, E from the mass balance, R from the residual. [file:6][file:14]
+    let ker = KerCalculator::new(SAT_CRITICAL_FIELDS).compute_with_evidence(&benefit, &coords, &evidence)?;
 
+    let mut corridor_tags = vec!["mar".to_string(), "sat".into(), "phoenix".into()];
+    if !warnings.is_empty() {
+        corridor_tags.push(INPUT_CLAMPED_TAG.into());
+    }
     let draft = DraftAssessment {
         user_did: user_did.to_string(),
         topic: "phoenix-mar-sat".into(),
-        base_triads: triad_inputs,
+        base_triads: ker.triad_inputs(),
         base_coords: coords,
-        evidence: vec![
-            format!("nitrate_in_mg_l={}", nitrate_in_mg_l),
-            format!("nitrate_out_mg_l={}", nitrate_out_mg_l),
-            format!("flow_m3_d={}", flow_m3_d),
-        ],
-        corridor_tags: vec!["mar".into(), "sat".into(), "phoenix".into()],
+        evidence,
+        corridor_tags,
     };
 
-    let shard = evaluate_draft(draft);
+    let shard = evaluate_draft(draft)?;
+    let improves = prev_shard.is_some_and(|prev| shard.improves_over(prev));
 
-    let improves = if let Some(prev) = prev_shard {
-        shard.improves_over(&prev)
-    } else {
-        false
-    };
+    Ok(SatEvaluation {
+        shard,
+        improves,
+        mass_balance,
+        ker,
+        warnings,
+    })
+}
+
+/// Evaluate whether a proposed configuration tightens the SAT pilot shard. [file:14]
+/// Carries no corridor evidence, so K is 0; use `evaluate_sat` to supply it.
+#[deprecated(note = "carries no corridor evidence and always emits K = 0; use `evaluate_sat` with `SatScenario::evidence`")]
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_sat_scenario(
+    user_did: &str,
+    nitrate_in_mg_l: f64,
+    nitrate_out_mg_l: f64,
+    flow_m3_d: f64,
+    hlr_m_d: f64,
+    pfas_ng_l: f64,
+    temp_c: f64,
+    prev_shard: Option<ResponseShard>,
+) -> Result<(ResponseShard, bool), SatEvalError> {
+    #[allow(deprecated)]
+    evaluate_sat_scenario_with(
+        &SatCorridorTable::phoenix_default(),
+        user_did,
+        nitrate_in_mg_l,
+        nitrate_out_mg_l,
+        flow_m3_d,
+        hlr_m_d,
+        pfas_ng_l,
+        temp_c,
+        prev_shard,
+    )
+}
 
-    Ok((shard, improves))
+/// As `evaluate_sat_scenario`, against a site's own corridor table; K is likewise 0. [file:14]
+#[deprecated(note = "carries no corridor evidence and always emits K = 0; use `evaluate_sat` with `SatScenario::evidence`")]
+#[allow(clippy::too_many_arguments)]
+pub fn evaluate_sat_scenario_with(
+    corridors: &SatCorridorTable,
+    user_did: &str,
+    nitrate_in_mg_l: f64,
+    nitrate_out_mg_l: f64,
+    flow_m3_d: f64,
+    hlr_m_d: f64,
+    pfas_ng_l: f64,
+    temp_c: f64,
+    prev_shard: Option<ResponseShard>,
+) -> Result<(ResponseShard, bool), SatEvalError> {
+    let scenario = SatScenario {
+        flow_m3_d,
+        contaminants: vec![Contaminant::nitrate(nitrate_in_mg_l, nitrate_out_mg_l)],
+        hlr_m_d,
+        pfas_ng_l,
+        temp_c,
+        evidence: Vec::new(),
+    };
+    let eval = evaluate_sat(corridors, user_did, &scenario, prev_shard.as_ref())?;
+    Ok((eval.shard, eval.improves))
 }
 
 #[cfg(test)]
@@ -126,6 +231,13 @@ mod tests {
         assert!(kg > 0.0);
     }
 
+    #[test]
+    fn eco_benefit_is_kg_per_day() {
+        // 1 mg/L removed from 1000 m3/d is 1 kg/d (1 mg/L = 1e-3 kg/m3), not the 1e-3 kg/d of a 1e-6 factor.
+        assert!((eco_benefit_kg_removed(2.0, 1.0, 1000.0) - 1.0).abs() < 1e-12);
+        assert!((eco_benefit_kg_removed(12.0, 3.0, 12_000.0) - 108.0).abs() < 1e-9);
+    }
+
     #[test]
     fn sat_risk_coords_range() {
         let coords = sat_risk_coords(0.1, 8.0, 22.0);
@@ -133,4 +245,83 @@ mod tests {
             assert!(c.value >= 0.0 && c.value <= 1.0);
         }
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn site_table_changes_the_residual() {
+        let strict = SatCorridorTable::from_csv(
+            "varid,units,safe,gold,hard,weight\n\
+             r_sat,m/d,0.02,0.05,0.10,0.4\n\
+             r_pfas,ng/L,2,4,8,0.4\n\
+             r_temp,degC,15,25,30,0.2\n",
+        )
+        .unwrap();
+        let (phoenix, _) =
+            evaluate_sat_scenario("bostrom18...", 10.0, 5.0, 1000.0, 0.1, 8.0, 22.0, None).unwrap();
+        let (site, _) =
+            evaluate_sat_scenario_with(&strict, "bostrom18...", 10.0, 5.0, 1000.0, 0.1, 8.0, 22.0, None).unwrap();
+        assert!(site.residual.vt > phoenix.residual.vt);
+        assert!(site.evidence.contains(&EvidenceRef::pilot("r_pfas", 8.0, "ng/L")));
+        assert!(site.evidence.contains(&EvidenceRef::band("r_pfas", "ng/L", 2.0, 4.0, 8.0)));
+    }
+
+    #[test]
+    fn e_reflects_every_contaminant_removed() {
+        let mut scenario = SatScenario {
+            flow_m3_d: 12_000.0,
+            contaminants: vec![Contaminant::nitrate(12.0, 3.0)],
+            hlr_m_d: 0.1,
+            pfas_ng_l: 3.9,
+            temp_c: 22.0,
+            evidence: Vec::new(),
+        };
+        let table = SatCorridorTable::phoenix_default();
+        let nitrate_only = evaluate_sat(&table, "bostrom18...", &scenario, None).unwrap();
+
+        scenario.contaminants.push(Contaminant::pfas(7.5, 3.9));
+        scenario.contaminants.push(Contaminant::pharma(25.0, 10.0));
+        let full = evaluate_sat(&table, "bostrom18...", &scenario, Some(&nitrate_only.shard)).unwrap();
+
+        assert!(full.shard.triad.eco_impact > nitrate_only.shard.triad.eco_impact);
+        assert!(full.improves);
+        assert_eq!(full.mass_balance.loads.len(), 3);
+        assert!(full.shard.evidence.contains(&EvidenceRef::pilot("pfas_out", 3.9, "ng/L")));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn scenario_triad_is_derived() {
+        let (shard, _) =
+            evaluate_sat_scenario("bostrom18...", 10.0, 5.0, 100_000.0, 0.1, 8.0, 22.0, None).unwrap();
+        assert_eq!(shard.triad.knowledge, 0.0);
+        assert_eq!(eco_benefit_kg_removed(10.0, 5.0, 100_000.0), 500.0);
+        assert!((shard.triad.eco_impact - 0.5).abs() < 1e-12);
+        assert!((shard.triad.risk_of_harm - shard.residual.vt).abs() < 1e-12);
+    }
+
+    #[test]
+    fn k_counts_only_supplied_corridor_rows() {
+        let table = SatCorridorTable::from_aln(include_str!("../../aln/particles/mar.satcell.corridors.phoenix2026.aln")).unwrap();
+        let loaded: Vec<EvidenceRef> = table
+            .rows()
+            .iter()
+            .map(|r| EvidenceRef::corridor_row(corridors::SAT_CORRIDOR_PARTICLE, r.var_id.clone()))
+            .collect();
+        let mut scenario = SatScenario {
+            flow_m3_d: 12_000.0,
+            contaminants: vec![Contaminant::nitrate(12.0, 3.0)],
+            hlr_m_d: 0.1,
+            pfas_ng_l: 3.9,
+            temp_c: 22.0,
+            evidence: loaded,
+        };
+        let full = evaluate_sat(&table, "bostrom18...", &scenario, None).unwrap();
+        assert_eq!(full.shard.triad.knowledge, 1.0);
+
+        scenario.evidence.retain(|e| e.corridor_var() != Some("r_pfas"));
+        let partial = evaluate_sat(&table, "bostrom18...", &scenario, None).unwrap();
+        assert!((partial.shard.triad.knowledge - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(partial.ker.knowledge.missing, ["r_pfas"]);
+        assert!(!partial.shard.evidence.iter().any(|e| e.corridor_var() == Some("r_pfas")));
+    }
 }
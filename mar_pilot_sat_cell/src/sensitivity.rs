@@ -0,0 +1,116 @@
+//! `ResidualKernel` over `evaluate_sat` for sensitivity and tornado analysis. [file:14]
+//!
+//! Inputs are the corridor readings (HLR, effluent PFAS, plume temperature)
+//! plus the flow; flow drives E only, so it shows as inert for V_t. Default
+//! ranges span each corridor row from safe to hard, widened to include the
+//! nominal reading.
+
+use response_shard::sensitivity::{InputSpec, ResidualKernel};
+use response_shard::Residual;
+
+use crate::corridors::SatCorridorTable;
+use crate::{evaluate_sat, SatEvalError, SatScenario};
+
+/// Input names, in `inputs()` order.
+pub const SAT_KERNEL_INPUTS: [&str; 4] = ["hlr_m_d", "pfas_ng_l", "temp_c", "flow_m3_d"];
+
+#[derive(Debug, Clone)]
+pub struct SatKernel {
+    pub corridors: SatCorridorTable,
+    pub base: SatScenario,
+    specs: Vec<InputSpec>,
+}
+
+impl SatKernel {
+    pub fn new(corridors: SatCorridorTable, base: SatScenario) -> Self {
+        let span = |var_id: &str, name: &str, nominal: f64| {
+            let (units, low, high) = corridors
+                .get(var_id)
+                .map_or((String::new(), nominal, nominal), |c| (c.units.clone(), c.safe, c.hard));
+            InputSpec::new(name, units, nominal, low.min(nominal), high.max(nominal))
+        };
+        let specs = vec![
+            span("r_sat", SAT_KERNEL_INPUTS[0], base.hlr_m_d),
+            span("r_pfas", SAT_KERNEL_INPUTS[1], base.pfas_ng_l),
+            span("r_temp", SAT_KERNEL_INPUTS[2], base.temp_c),
+            InputSpec::new(SAT_KERNEL_INPUTS[3], "m3/d", base.flow_m3_d, 0.0, 2.0 * base.flow_m3_d),
+        ];
+        Self { corridors, base, specs }
+    }
+
+    /// Replace the declared range of `name`; unknown names are ignored.
+    pub fn with_range(mut self, name: &str, low: f64, high: f64) -> Self {
+        if let Some(s) = self.specs.iter_mut().find(|s| s.name == name) {
+            s.low = low;
+            s.high = high;
+        }
+        self
+    }
+
+    fn scenario(&self, x: &[f64]) -> SatScenario {
+        SatScenario {
+            hlr_m_d: x[0],
+            pfas_ng_l: x[1],
+            temp_c: x[2],
+            flow_m3_d: x[3],
+            ..self.base.clone()
+        }
+    }
+}
+
+impl ResidualKernel for SatKernel {
+    type Error = SatEvalError;
+
+    fn inputs(&self) -> Vec<InputSpec> {
+        self.specs.clone()
+    }
+
+    fn residual(&self, x: &[f64]) -> Result<Residual, SatEvalError> {
+        let eval = evaluate_sat(&self.corridors, "sensitivity", &self.scenario(x), None)?;
+        Ok(eval.shard.residual)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benefit::Contaminant;
+    use response_shard::sensitivity::SensitivityAnalysis;
+
+    fn base(pfas_ng_l: f64) -> SatScenario {
+        SatScenario {
+            flow_m3_d: 10_000.0,
+            contaminants: vec![Contaminant::nitrate(12.0, 3.0)],
+            hlr_m_d: 0.1,
+            pfas_ng_l,
+            temp_c: 22.0,
+            evidence: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn partials_and_tornado_for_the_phoenix_cell() {
+        let kernel = SatKernel::new(SatCorridorTable::phoenix_default(), base(8.0));
+        let report = SensitivityAnalysis::default().analyze(&kernel).unwrap();
+
+        // r_temp = (T - 15) / 15 with weight 0.2.
+        let temp = report.partial("temp_c").unwrap();
+        assert!((temp.d_coords["r_temp"] - 1.0 / 15.0).abs() < 1e-9);
+        assert!((temp.d_vt - 0.2 / 15.0).abs() < 1e-9);
+        assert_eq!(report.partial("flow_m3_d").unwrap().d_vt, 0.0);
+        assert!(!report.is_clamped("flow_m3_d"));
+
+        let order: Vec<&str> = report.tornado.iter().map(|r| r.input.as_str()).collect();
+        assert_eq!(order, ["hlr_m_d", "pfas_ng_l", "temp_c", "flow_m3_d"]);
+    }
+
+    #[test]
+    fn pfas_below_its_safe_band_sits_on_a_clamp() {
+        let kernel =
+            SatKernel::new(SatCorridorTable::phoenix_default(), base(3.9)).with_range("pfas_ng_l", 0.0, 30.0);
+        let report = SensitivityAnalysis::default().analyze(&kernel).unwrap();
+        assert_eq!(report.partial("pfas_ng_l").unwrap().d_vt, 0.0);
+        assert!(report.is_clamped("pfas_ng_l"));
+        assert!(report.tornado.iter().find(|r| r.input == "pfas_ng_l").unwrap().on_clamp);
+    }
+}
@@ -0,0 +1,348 @@
+//! Discrete-time SAT basin simulator: one step per day. [file:7][file:14]
+//!
+//! The basin alternates wet and dry phases. While wet, applied loading
+//! infiltrates up to the clogged capacity and builds the biomat
+//! (`fouling_index`, same meaning as in `MarShard`); drying recovers part of
+//! it and a cleaning pass (one `cleaning_dose_eq`) removes most of the rest.
+//! The plume temperature relaxes toward the influent while wet and toward
+//! ambient while dry. Every step is evaluated into a `ResponseShard` and
+//! checked with `safestep_with` against the step before, so a schedule can be
+//! tried here before it is run on the basin.
+
+use response_shard::aln_invariants::{safestep_with, CorridorDecision, SafeStepDecision, SafeStepPolicy};
+use response_shard::evidence::EvidenceRef;
+use response_shard::ResponseShard;
+use thiserror::Error;
+
+use crate::benefit::Contaminant;
+use crate::corridors::SatCorridorTable;
+use crate::{evaluate_sat, SatEvalError, SatEvaluation, SatScenario};
+
+/// Upper bound on `fouling_index`; keeps some open area so loading stays finite.
+pub const FOULING_INDEX_MAX: f64 = 0.99;
+
+#[derive(Debug, Error)]
+pub enum SimError {
+    #[error("basin parameter `{field}` = {value} is out of range")]
+    InvalidConfig { field: &'static str, value: f64 },
+    #[error("schedule has no wet days")]
+    NoWetDays,
+    #[error("day {day}: {source}")]
+    Eval {
+        day: u32,
+        #[source]
+        source: SatEvalError,
+    },
+}
+
+/// Physical parameters of one SAT basin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SatBasinConfig {
+    pub area_m2: f64,
+    /// Infiltration capacity of the clean basin floor, m/d.
+    pub clean_infiltration_m_d: f64,
+    /// `fouling_index` gained per m of water infiltrated (scaled by the open fraction).
+    pub fouling_per_m: f64,
+    /// Fraction of `fouling_index` recovered per dry day.
+    pub dry_recovery_per_d: f64,
+    /// Clean on the first dry day at or above this `fouling_index`; `None` never cleans.
+    pub clean_at_fouling: Option<f64>,
+    /// Fraction of `fouling_index` removed by one cleaning dose.
+    pub cleaning_efficiency: f64,
+    pub influent_temp_c: f64,
+    pub ambient_temp_c: f64,
+    /// Fraction of the gap to the target temperature closed per day.
+    pub thermal_relax_per_d: f64,
+    /// Effluent PFAS reading for the `r_pfas` corridor.
+    pub pfas_out_ng_l: f64,
+    /// Pilot in/out concentrations feeding the mass balance.
+    pub contaminants: Vec<Contaminant>,
+    /// Evidence attached to every evaluated day; K counts its corridor rows.
+    pub evidence: Vec<EvidenceRef>,
+}
+
+impl SatBasinConfig {
+    /// Placeholder Phoenix pilot basin; pilot data should tighten. [file:14]
+    pub fn phoenix_pilot() -> Self {
+        Self {
+            area_m2: 50_000.0,
+            clean_infiltration_m_d: 0.5,
+            fouling_per_m: 0.05,
+            dry_recovery_per_d: 0.1,
+            clean_at_fouling: Some(0.4),
+            cleaning_efficiency: 0.8,
+            influent_temp_c: 24.0,
+            ambient_temp_c: 20.0,
+            thermal_relax_per_d: 0.3,
+            pfas_out_ng_l: 3.9,
+            contaminants: vec![Contaminant::nitrate(12.0, 3.0), Contaminant::pfas(7.5, 3.9)],
+            evidence: Vec::new(),
+        }
+    }
+
+    fn check(&self) -> Result<(), SimError> {
+        let positive = [
+            ("area_m2", self.area_m2),
+            ("clean_infiltration_m_d", self.clean_infiltration_m_d),
+        ];
+        let non_negative = [("fouling_per_m", self.fouling_per_m), ("pfas_out_ng_l", self.pfas_out_ng_l)];
+        let fractions = [
+            ("dry_recovery_per_d", self.dry_recovery_per_d),
+            ("cleaning_efficiency", self.cleaning_efficiency),
+            ("thermal_relax_per_d", self.thermal_relax_per_d),
+            ("clean_at_fouling", self.clean_at_fouling.unwrap_or(0.0)),
+        ];
+        let temps = [("influent_temp_c", self.influent_temp_c), ("ambient_temp_c", self.ambient_temp_c)];
+
+        let bad = positive
+            .iter()
+            .find(|(_, v)| !(v.is_finite() && *v > 0.0))
+            .or_else(|| non_negative.iter().find(|(_, v)| !(v.is_finite() && *v >= 0.0)))
+            .or_else(|| fractions.iter().find(|(_, v)| !(0.0..=1.0).contains(v)))
+            .or_else(|| temps.iter().find(|(_, v)| !v.is_finite()));
+        match bad {
+            Some(&(field, value)) => Err(SimError::InvalidConfig { field, value }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Repeating wet/dry cycle with a fixed applied loading while wet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SatSchedule {
+    pub wet_days: u32,
+    pub dry_days: u32,
+    /// Applied hydraulic loading during wet days, m/d.
+    pub hlr_m_d: f64,
+}
+
+impl SatSchedule {
+    pub fn is_wet(&self, day: u32) -> bool {
+        day % (self.wet_days + self.dry_days) < self.wet_days
+    }
+
+    /// Fraction of the cycle spent wet.
+    pub fn wet_fraction(&self) -> f64 {
+        f64::from(self.wet_days) / f64::from(self.wet_days + self.dry_days)
+    }
+}
+
+/// Basin state over one simulated day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SatBasinState {
+    pub day: u32,
+    pub wet: bool,
+    /// Clogged fraction of the floor during the day, in [0, `FOULING_INDEX_MAX`].
+    pub fouling_index: f64,
+    /// Cumulative cleaning doses applied up to and including this day.
+    pub cleaning_dose_eq: f64,
+    /// Capacity of the clogged floor, m/d.
+    pub infiltration_capacity_m_d: f64,
+    /// Loading that actually infiltrated, m/d.
+    pub infiltrated_m_d: f64,
+    /// Applied loading the floor could not take, m/d.
+    pub ponding_m_d: f64,
+    /// Infiltrated loading over the open area; the `r_sat` reading.
+    pub effective_hlr_m_d: f64,
+    pub plume_temp_c: f64,
+}
+
+/// One simulated day: state, shard, and the safestep verdict against the day before.
+#[derive(Debug, Clone)]
+pub struct SatStep {
+    pub state: SatBasinState,
+    pub evaluation: SatEvaluation,
+    /// `None` on the first day when no starting shard was given.
+    pub safestep: Option<SafeStepDecision>,
+}
+
+impl SatStep {
+    pub fn decision(&self) -> CorridorDecision {
+        self.safestep.as_ref().map_or(CorridorDecision::Ok, |s| s.decision)
+    }
+}
+
+/// All steps of one run, in day order.
+#[derive(Debug, Clone)]
+pub struct SatRun {
+    pub steps: Vec<SatStep>,
+}
+
+impl SatRun {
+    /// First step whose decision is `decision`.
+    pub fn first(&self, decision: CorridorDecision) -> Option<&SatStep> {
+        self.steps.iter().find(|s| s.decision() == decision)
+    }
+
+    /// True when no step was stopped.
+    pub fn passes(&self) -> bool {
+        self.first(CorridorDecision::Stop).is_none()
+    }
+
+    /// Steps on which `var_id` (or `V_t`) crossed a limit.
+    pub fn breaches_of<'a>(&'a self, var_id: &'a str) -> impl Iterator<Item = &'a SatStep> + 'a {
+        self.steps.iter().filter(move |s| {
+            s.safestep.as_ref().is_some_and(|d| d.offending().contains(&var_id))
+        })
+    }
+
+    pub fn vt_series(&self) -> Vec<f64> {
+        self.steps.iter().map(|s| s.evaluation.shard.residual.vt).collect()
+    }
+
+    pub fn last_shard(&self) -> Option<&ResponseShard> {
+        self.steps.last().map(|s| &s.evaluation.shard)
+    }
+}
+
+/// Daily SAT basin simulator over a site's corridor table.
+#[derive(Debug, Clone)]
+pub struct SatSimulator {
+    pub config: SatBasinConfig,
+    pub corridors: SatCorridorTable,
+    pub policy: SafeStepPolicy,
+}
+
+impl SatSimulator {
+    pub fn new(config: SatBasinConfig, corridors: SatCorridorTable) -> Self {
+        Self {
+            config,
+            corridors,
+            policy: SafeStepPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: SafeStepPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Run `days` days from a clean basin at ambient temperature; day 0 is checked
+    /// against `start` when given.
+    pub fn run(
+        &self,
+        user_did: &str,
+        schedule: &SatSchedule,
+        days: u32,
+        start: Option<&ResponseShard>,
+    ) -> Result<SatRun, SimError> {
+        self.config.check()?;
+        if schedule.wet_days == 0 {
+            return Err(SimError::NoWetDays);
+        }
+        if !(schedule.hlr_m_d.is_finite() && schedule.hlr_m_d >= 0.0) {
+            return Err(SimError::InvalidConfig {
+                field: "hlr_m_d",
+                value: schedule.hlr_m_d,
+            });
+        }
+
+        let cfg = &self.config;
+        let mut fouling = 0.0_f64;
+        let mut doses = 0.0;
+        let mut temp = cfg.ambient_temp_c;
+        let mut steps: Vec<SatStep> = Vec::with_capacity(days as usize);
+
+        for day in 0..days {
+            let wet = schedule.is_wet(day);
+            if !wet && cfg.clean_at_fouling.is_some_and(|at| fouling >= at) {
+                fouling *= 1.0 - cfg.cleaning_efficiency;
+                doses += 1.0;
+            }
+
+            let open = 1.0 - fouling;
+            let capacity = cfg.clean_infiltration_m_d * open;
+            let applied = if wet { schedule.hlr_m_d } else { 0.0 };
+            let infiltrated = applied.min(capacity);
+            let target = if wet { cfg.influent_temp_c } else { cfg.ambient_temp_c };
+            temp += cfg.thermal_relax_per_d * (target - temp);
+
+            let state = SatBasinState {
+                day,
+                wet,
+                fouling_index: fouling,
+                cleaning_dose_eq: doses,
+                infiltration_capacity_m_d: capacity,
+                infiltrated_m_d: infiltrated,
+                ponding_m_d: applied - infiltrated,
+                effective_hlr_m_d: infiltrated / open,
+                plume_temp_c: temp,
+            };
+
+            // Biomat grows with what went through the floor and dries back when idle.
+            fouling = if wet {
+                (fouling + cfg.fouling_per_m * infiltrated * open).min(FOULING_INDEX_MAX)
+            } else {
+                fouling * (1.0 - cfg.dry_recovery_per_d)
+            };
+
+            let scenario = SatScenario {
+                flow_m3_d: infiltrated * cfg.area_m2,
+                contaminants: cfg.contaminants.clone(),
+                hlr_m_d: state.effective_hlr_m_d,
+                pfas_ng_l: cfg.pfas_out_ng_l,
+                temp_c: temp,
+                evidence: cfg.evidence.clone(),
+            };
+            let prev = steps.last().map(|s| &s.evaluation.shard).or(start);
+            let evaluation = evaluate_sat(&self.corridors, user_did, &scenario, prev)
+                .map_err(|source| SimError::Eval { day, source })?;
+            let safestep = prev.map(|p| safestep_with(&p.residual, &evaluation.shard.residual, &self.policy));
+
+            steps.push(SatStep {
+                state,
+                evaluation,
+                safestep,
+            });
+        }
+        Ok(SatRun { steps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fouling_follows_wet_dry_cycling_and_cleaning() {
+        let mut config = SatBasinConfig::phoenix_pilot();
+        config.clean_at_fouling = Some(0.15);
+        let schedule = SatSchedule { wet_days: 10, dry_days: 4, hlr_m_d: 0.48 };
+        let run = SatSimulator::new(config, SatCorridorTable::phoenix_default())
+            .run("bostrom18...", &schedule, 28, None)
+            .unwrap();
+        let s = |day: usize| run.steps[day].state;
+
+        // Wet: biomat grows, capacity falls, plume warms toward the influent.
+        assert!(s(9).fouling_index > s(1).fouling_index);
+        assert!(s(9).infiltration_capacity_m_d < s(1).infiltration_capacity_m_d);
+        assert!(s(9).plume_temp_c > s(0).plume_temp_c);
+        assert!(s(9).ponding_m_d > 0.0);
+        // Dry: cleaned once past the threshold, then drying recovers the rest.
+        assert_eq!(s(9).cleaning_dose_eq, 0.0);
+        assert_eq!(s(10).cleaning_dose_eq, 1.0);
+        assert!(s(10).fouling_index < s(9).fouling_index);
+        assert!(s(13).fouling_index < s(11).fouling_index);
+        assert_eq!(s(12).infiltrated_m_d, 0.0);
+        assert_eq!(run.steps[12].evaluation.shard.triad.eco_impact, 0.0);
+    }
+
+    #[test]
+    fn safestep_flags_overloaded_schedules() {
+        let sim = SatSimulator::new(SatBasinConfig::phoenix_pilot(), SatCorridorTable::phoenix_default());
+        let gentle = SatSchedule { wet_days: 3, dry_days: 4, hlr_m_d: 0.1 };
+        let run = sim.run("bostrom18...", &gentle, 21, None).unwrap();
+        assert!(run.passes());
+        assert_eq!(run.breaches_of("r_sat").count(), 0);
+
+        let mut config = SatBasinConfig::phoenix_pilot();
+        config.clean_at_fouling = None;
+        let overloaded = SatSchedule { wet_days: 20, dry_days: 1, hlr_m_d: 0.14 };
+        let run = SatSimulator::new(config, SatCorridorTable::phoenix_default())
+            .run("bostrom18...", &overloaded, 21, run.last_shard())
+            .unwrap();
+        let first = run.breaches_of("r_sat").next().expect("r_sat should leave its gold band");
+        assert_eq!(first.decision(), CorridorDecision::Derate);
+        assert!(first.state.fouling_index > 0.0);
+    }
+}
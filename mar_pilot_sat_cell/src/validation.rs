@@ -0,0 +1,232 @@
+//! Input validation for SAT scenarios. [file:7][file:14]
+//!
+//! Strict mode rejects a scenario with every bad field listed. "Clamp and
+//! warn" is opt-in and only ever clamps toward the unsafe side: a bad
+//! corridor reading becomes that corridor's hard limit (r_x = 1) and a bad
+//! concentration pair or flow claims no removal. Each replacement is returned
+//! as a warning, and the shard is tagged `INPUT_CLAMPED_TAG`.
+
+use std::ops::RangeInclusive;
+
+use thiserror::Error;
+
+use crate::corridors::SatCorridorTable;
+use crate::SatScenario;
+
+/// Corridor tag added to shards built from clamped inputs.
+pub const INPUT_CLAMPED_TAG: &str = "input-clamped";
+
+/// Physical ranges; readings outside them are telemetry faults, not risk.
+pub const FLOW_RANGE_M3_D: RangeInclusive<f64> = 0.0..=f64::MAX;
+pub const HLR_RANGE_M_D: RangeInclusive<f64> = 0.0..=f64::MAX;
+pub const PFAS_RANGE_NG_L: RangeInclusive<f64> = 0.0..=f64::MAX;
+pub const TEMP_RANGE_C: RangeInclusive<f64> = 0.0..=100.0;
+pub const CONCENTRATION_RANGE: RangeInclusive<f64> = 0.0..=f64::MAX;
+/// Hazard weights are relative; 0 drops a contaminant from the benefit.
+pub const HAZARD_WEIGHT_RANGE: RangeInclusive<f64> = 0.0..=f64::MAX;
+
+/// One rejected input, with the field, the value and what was allowed.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum InputError {
+    #[error("`{field}` = {value} is not finite")]
+    NonFinite { field: String, value: f64 },
+    #[error("`{field}` = {value} is outside [{min}, {max}]")]
+    OutOfRange { field: String, value: f64, min: f64, max: f64 },
+    #[error("`{contaminant}` outlet {c_out} is above inlet {c_in}")]
+    OutletAboveInlet { contaminant: String, c_in: f64, c_out: f64 },
+}
+
+/// Every rejected input of one scenario, in field order.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{} invalid input(s): {}", .0.len(), join(.0))]
+pub struct InputErrors(pub Vec<InputError>);
+
+impl InputError {
+    /// Scenario field the error is about; `<name>.c_out` for a reversed pair.
+    pub fn field(&self) -> String {
+        match self {
+            InputError::NonFinite { field, .. } | InputError::OutOfRange { field, .. } => field.clone(),
+            InputError::OutletAboveInlet { contaminant, .. } => format!("{}.c_out", contaminant),
+        }
+    }
+}
+
+fn join(errors: &[InputError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl InputErrors {
+    pub fn errors(&self) -> &[InputError] {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    #[default]
+    Strict,
+    /// Replace bad inputs with their conservative value and warn.
+    ClampAndWarn,
+}
+
+/// A replacement made under `ValidationMode::ClampAndWarn`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputWarning {
+    /// What was wrong; `error.field()` names the replaced field.
+    pub error: InputError,
+    pub replaced_with: f64,
+}
+
+fn check_range(field: &str, value: f64, range: &RangeInclusive<f64>) -> Option<InputError> {
+    if !value.is_finite() {
+        Some(InputError::NonFinite { field: field.into(), value })
+    } else if !range.contains(&value) {
+        Some(InputError::OutOfRange {
+            field: field.into(),
+            value,
+            min: *range.start(),
+            max: *range.end(),
+        })
+    } else {
+        None
+    }
+}
+
+struct Pass {
+    mode: ValidationMode,
+    errors: Vec<InputError>,
+    warnings: Vec<InputWarning>,
+}
+
+impl Pass {
+    /// Record `error`; in clamp mode, write `fallback` (when there is one) into `slot`.
+    fn fail(&mut self, error: InputError, slot: &mut f64, fallback: Option<f64>) {
+        match (self.mode, fallback) {
+            (ValidationMode::ClampAndWarn, Some(v)) => {
+                *slot = v;
+                self.warnings.push(InputWarning { error, replaced_with: v });
+            }
+            _ => self.errors.push(error),
+        }
+    }
+
+    fn range(&mut self, field: &str, slot: &mut f64, range: &RangeInclusive<f64>, fallback: Option<f64>) {
+        if let Some(e) = check_range(field, *slot, range) {
+            self.fail(e, slot, fallback);
+        }
+    }
+}
+
+/// Check `scenario`; in clamp mode return the repaired scenario and what was replaced.
+pub fn check_scenario(
+    scenario: &SatScenario,
+    corridors: &SatCorridorTable,
+    mode: ValidationMode,
+) -> Result<(SatScenario, Vec<InputWarning>), InputErrors> {
+    let mut out = scenario.clone();
+    let mut pass = Pass {
+        mode,
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+    let hard = |var_id: &str| corridors.get(var_id).map(|c| c.hard);
+
+    pass.range("flow_m3_d", &mut out.flow_m3_d, &FLOW_RANGE_M3_D, Some(0.0));
+    pass.range("hlr_m_d", &mut out.hlr_m_d, &HLR_RANGE_M_D, hard("r_sat"));
+    pass.range("pfas_ng_l", &mut out.pfas_ng_l, &PFAS_RANGE_NG_L, hard("r_pfas"));
+    pass.range("temp_c", &mut out.temp_c, &TEMP_RANGE_C, hard("r_temp"));
+
+    for c in out.contaminants.iter_mut() {
+        let (f_in, f_out, f_w) = (
+            format!("{}.c_in", c.name),
+            format!("{}.c_out", c.name),
+            format!("{}.hazard_weight", c.name),
+        );
+        pass.range(&f_w, &mut c.hazard_weight, &HAZARD_WEIGHT_RANGE, Some(0.0));
+        pass.range(&f_in, &mut c.c_in, &CONCENTRATION_RANGE, Some(0.0));
+        // With no valid inlet to compare against, claim no removal.
+        let c_in = c.c_in;
+        let in_ok = check_range(&f_in, c_in, &CONCENTRATION_RANGE).is_none();
+        pass.range(&f_out, &mut c.c_out, &CONCENTRATION_RANGE, in_ok.then_some(c_in));
+        if c.c_out.is_finite() && in_ok && c.c_out > c_in {
+            let error = InputError::OutletAboveInlet {
+                contaminant: c.name.clone(),
+                c_in,
+                c_out: c.c_out,
+            };
+            pass.fail(error, &mut c.c_out, Some(c_in));
+        }
+    }
+
+    if pass.errors.is_empty() {
+        Ok((out, pass.warnings))
+    } else {
+        Err(InputErrors(pass.errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benefit::Contaminant;
+    use crate::{evaluate_sat, evaluate_sat_with_mode, SatEvalError};
+
+    fn bad() -> SatScenario {
+        SatScenario {
+            flow_m3_d: -10.0,
+            contaminants: vec![Contaminant::nitrate(3.0, 12.0), Contaminant::pfas(7.5, 3.9)],
+            hlr_m_d: 0.1,
+            pfas_ng_l: 8.0,
+            temp_c: f64::NAN,
+            evidence: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn strict_mode_names_every_bad_field() {
+        let table = SatCorridorTable::phoenix_default();
+        let Err(SatEvalError::Input(errors)) = evaluate_sat(&table, "bostrom18...", &bad(), None) else {
+            panic!("bad telemetry was accepted");
+        };
+        assert_eq!(errors.errors().len(), 3);
+        assert_eq!(
+            errors.errors()[0],
+            InputError::OutOfRange {
+                field: "flow_m3_d".into(),
+                value: -10.0,
+                min: 0.0,
+                max: f64::MAX
+            }
+        );
+        assert!(matches!(&errors.errors()[1], InputError::NonFinite { field, .. } if field == "temp_c"));
+        assert_eq!(
+            errors.errors()[2],
+            InputError::OutletAboveInlet {
+                contaminant: "nitrate".into(),
+                c_in: 3.0,
+                c_out: 12.0
+            }
+        );
+    }
+
+    #[test]
+    fn clamping_never_looks_safer_than_the_telemetry() {
+        let table = SatCorridorTable::phoenix_default();
+        let mut fixed = bad();
+        fixed.flow_m3_d = 10_000.0;
+        let eval =
+            evaluate_sat_with_mode(&table, "bostrom18...", &fixed, None, ValidationMode::ClampAndWarn).unwrap();
+
+        let fields: Vec<String> = eval.warnings.iter().map(|w| w.error.field()).collect();
+        assert_eq!(fields, ["temp_c", "nitrate.c_out"]);
+        // NaN temperature goes to the 30 °C hard limit; reversed nitrate claims nothing.
+        let r_temp = eval.shard.residual.coords.iter().find(|c| c.var_id == "r_temp").unwrap();
+        assert_eq!(r_temp.value, 1.0);
+        assert_eq!(eval.mass_balance.share("nitrate"), 0.0);
+        assert!(eval.shard.corridor_tags.iter().any(|t| t == INPUT_CLAMPED_TAG));
+    }
+}
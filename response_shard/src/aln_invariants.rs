@@ -1,6 +1,12 @@
 //! ALN-style invariants as pure Rust functions. [file:7]
 
-use crate::{Residual, RiskCoord};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::delta::Axis;
+use crate::template::ResponseShardTemplate;
+use crate::{Residual, RiskCoord, Triad};
 
 /// Invariant 1: no_corridor_no_build – every required variable must have a corridor row. [file:7]
 pub fn no_corridor_no_build(required_vars: &[&str], coords: &[RiskCoord]) -> bool {
@@ -32,7 +38,239 @@ pub fn safestep(prev: &Residual, next: &Residual) -> CorridorDecision {
     CorridorDecision::Ok
 }
 
+/// Tuning for `safestep_with`. [file:7]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SafeStepPolicy {
+    /// A step is inside the safe interior when every r_x ≤ its `safe` band + eps;
+    /// there V_t may float. Outside it, V_t must not rise.
+    pub safe_interior_eps: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreachKind {
+    /// r_x above its own `hard` limit (or below 0).
+    Hard,
+    /// r_x above its `gold` band but within `hard`.
+    Gold,
+    /// V_t rose outside the safe interior.
+    Lyapunov,
+}
+
+/// One limit a step crossed; `var_id` is `V_t` for `Lyapunov`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    pub kind: BreachKind,
+    pub var_id: String,
+    pub value: f64,
+    pub limit: f64,
+}
+
+impl Breach {
+    /// Distance past the limit, always ≥ 0.
+    pub fn excess(&self) -> f64 {
+        (self.value - self.limit).abs()
+    }
+}
+
+/// `safestep_with` decision plus every breach behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeStepDecision {
+    pub decision: CorridorDecision,
+    pub breaches: Vec<Breach>,
+}
+
+impl SafeStepDecision {
+    /// Coordinates (and `V_t`) that crossed a limit.
+    pub fn offending(&self) -> Vec<&str> {
+        self.breaches.iter().map(|b| b.var_id.as_str()).collect()
+    }
+
+    /// Largest excess among the breaches of the kind that decided the outcome.
+    pub fn magnitude(&self) -> f64 {
+        let decisive = |b: &&Breach| match self.decision {
+            CorridorDecision::Stop => b.kind == BreachKind::Hard,
+            _ => true,
+        };
+        self.breaches.iter().filter(decisive).map(Breach::excess).fold(0.0, f64::max)
+    }
+}
+
+/// Invariant 2 with per-coordinate limits: Stop past `hard`, Derate past `gold`
+/// or when V_t rises outside the safe interior. A non-finite r_x is a `Hard`
+/// breach and a non-finite V_t a `Lyapunov` one. [file:7]
+pub fn safestep_with(prev: &Residual, next: &Residual, policy: &SafeStepPolicy) -> SafeStepDecision {
+    let mut breaches = Vec::new();
+    for c in &next.coords {
+        // A NaN or infinite reading is unreadable telemetry, never a pass.
+        let (kind, limit) = if !c.value.is_finite() || c.value > c.hard {
+            (BreachKind::Hard, c.hard)
+        } else if c.value < 0.0 {
+            (BreachKind::Hard, 0.0)
+        } else if c.value > c.gold {
+            (BreachKind::Gold, c.gold)
+        } else {
+            continue;
+        };
+        breaches.push(Breach {
+            kind,
+            var_id: c.var_id.clone(),
+            value: c.value,
+            limit,
+        });
+    }
+
+    let in_interior = next.coords.iter().all(|c| c.value <= c.safe + policy.safe_interior_eps);
+    if !next.vt.is_finite() || (!in_interior && next.vt > prev.vt) {
+        breaches.push(Breach {
+            kind: BreachKind::Lyapunov,
+            var_id: "V_t".into(),
+            value: next.vt,
+            limit: prev.vt,
+        });
+    }
+
+    let decision = if breaches.iter().any(|b| b.kind == BreachKind::Hard) {
+        CorridorDecision::Stop
+    } else if breaches.is_empty() {
+        CorridorDecision::Ok
+    } else {
+        CorridorDecision::Derate
+    };
+    SafeStepDecision { decision, breaches }
+}
+
+/// Axes a `KerGate` checks; unlike `delta::Axis` there is no V_t.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KerAxis {
+    K,
+    E,
+    R,
+}
+
+/// Hard K/E/R gate; same fields as the `KerGate` in `ker_spine`. [file:1][file:9]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KerGate {
+    pub k_min: f64,
+    pub e_min: f64,
+    pub r_max: f64,
+}
+
+impl KerGate {
+    /// Gate from a template's `default_K_min`, `default_E_min`, `default_R_max`.
+    pub fn from_template(template: &ResponseShardTemplate) -> Self {
+        Self {
+            k_min: template.default_k_min,
+            e_min: template.default_e_min,
+            r_max: template.default_r_max,
+        }
+    }
+
+    /// Axes on which `triad` misses the gate.
+    pub fn misses(&self, triad: &Triad) -> Vec<KerAxis> {
+        let mut out = Vec::new();
+        // A non-finite value misses the gate rather than failing every comparison.
+        if triad.knowledge < self.k_min || !triad.knowledge.is_finite() {
+            out.push(KerAxis::K);
+        }
+        if triad.eco_impact < self.e_min || !triad.eco_impact.is_finite() {
+            out.push(KerAxis::E);
+        }
+        if triad.risk_of_harm > self.r_max || !triad.risk_of_harm.is_finite() {
+            out.push(KerAxis::R);
+        }
+        out
+    }
+
+    pub fn admits(&self, triad: &Triad) -> bool {
+        self.misses(triad).is_empty()
+    }
+}
+
+/// Nominal K/E/R a research line aims for; same fields as in `ker_spine`. [file:9]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KerBandTarget {
+    pub k_nominal: f64,
+    pub e_nominal: f64,
+    pub r_nominal: f64,
+}
+
+impl KerBandTarget {
+    pub fn reached_by(&self, triad: &Triad) -> bool {
+        triad.knowledge >= self.k_nominal && triad.eco_impact >= self.e_nominal && triad.risk_of_harm <= self.r_nominal
+    }
+}
+
+/// One prev → next move of a triad.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KerTransition {
+    pub prev: Triad,
+    pub next: Triad,
+}
+
+/// Whether an axis failed against the gate or by degrading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KerFailureKind {
+    Threshold,
+    Monotonicity,
+}
+
+/// One failed `ker_delta` check; `limit` is the gate bound or the previous value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KerFailure {
+    pub axis: Axis,
+    pub kind: KerFailureKind,
+    pub value: f64,
+    pub limit: f64,
+}
+
+impl fmt::Display for KerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            KerFailureKind::Threshold => write!(f, "{} {:.3} misses gate {:.3}", self.axis.as_str(), self.value, self.limit),
+            KerFailureKind::Monotonicity => write!(f, "{} degraded from {:.3} to {:.3}", self.axis.as_str(), self.limit, self.value),
+        }
+    }
+}
+
+impl KerTransition {
+    /// Every monotonicity and threshold failure, in K, E, R order; a non-finite value is a threshold failure.
+    pub fn evaluate(&self, gate: &KerGate) -> Vec<KerFailure> {
+        let (p, n) = (self.prev, self.next);
+        let axes = [
+            (Axis::K, p.knowledge, n.knowledge, gate.k_min),
+            (Axis::E, p.eco_impact, n.eco_impact, gate.e_min),
+            (Axis::R, p.risk_of_harm, n.risk_of_harm, gate.r_max),
+        ];
+        let mut out = Vec::new();
+        for (axis, prev, next, bound) in axes {
+            // K and E must not fall and stay above their minimum; R the reverse.
+            // A non-finite value on either side is a threshold failure, since no comparison holds.
+            let unreadable = !prev.is_finite() || !next.is_finite();
+            let (degraded, missed) = if axis == Axis::R {
+                (next > prev, next > bound)
+            } else {
+                (next < prev, next < bound)
+            };
+            let (degraded, missed) = (degraded && !unreadable, missed || unreadable);
+            if degraded {
+                out.push(KerFailure { axis, kind: KerFailureKind::Monotonicity, value: next, limit: prev });
+            }
+            if missed {
+                out.push(KerFailure { axis, kind: KerFailureKind::Threshold, value: next, limit: bound });
+            }
+        }
+        out
+    }
+
+    pub fn passes(&self, gate: &KerGate) -> bool {
+        self.evaluate(gate).is_empty()
+    }
+}
+
 /// Invariant 3: ker_delta – require non-degrading K/E/R against thresholds. [file:6]
+///
+/// Positional form kept for existing callers; prefer `KerTransition::evaluate`.
+#[allow(clippy::too_many_arguments)]
 pub fn ker_delta(
     prev_k: f64,
     prev_e: f64,
@@ -44,12 +282,11 @@ pub fn ker_delta(
     min_e: f64,
     max_r: f64,
 ) -> bool {
-    next_k >= prev_k
-        && next_e >= prev_e
-        && next_r <= prev_r
-        && next_k >= min_k
-        && next_e >= min_e
-        && next_r <= max_r
+    KerTransition {
+        prev: Triad { knowledge: prev_k, eco_impact: prev_e, risk_of_harm: prev_r },
+        next: Triad { knowledge: next_k, eco_impact: next_e, risk_of_harm: next_r },
+    }
+    .passes(&KerGate { k_min: min_k, e_min: min_e, r_max: max_r })
 }
 
 #[cfg(test)]
@@ -79,9 +316,101 @@ mod tests {
         assert_eq!(safestep(&prev, &next), CorridorDecision::Derate);
     }
 
+    fn coord(var_id: &str, value: f64) -> RiskCoord {
+        RiskCoord {
+            var_id: var_id.into(),
+            value,
+            safe: 0.1,
+            gold: 0.5,
+            hard: 0.8,
+            weight: 0.5,
+        }
+    }
+
+    #[test]
+    fn safestep_with_uses_coordinate_limits() {
+        let prev = Residual::from_coords(vec![coord("r_sat", 0.3), coord("r_pfas", 0.2)]);
+        let next = Residual::from_coords(vec![coord("r_sat", 0.9), coord("r_pfas", 0.6)]);
+        let d = safestep_with(&prev, &next, &SafeStepPolicy::default());
+        assert_eq!(d.decision, CorridorDecision::Stop);
+        assert_eq!(d.offending(), vec!["r_sat", "r_pfas", "V_t"]);
+        assert!((d.magnitude() - 0.1).abs() < 1e-12);
+
+        let gold = Residual::from_coords(vec![coord("r_sat", 0.6), coord("r_pfas", 0.1)]);
+        let d = safestep_with(&next, &gold, &SafeStepPolicy::default());
+        assert_eq!(d.decision, CorridorDecision::Derate);
+        assert_eq!(d.offending(), vec!["r_sat"]);
+    }
+
+    #[test]
+    fn safestep_with_lets_vt_float_in_safe_interior() {
+        let prev = Residual::from_coords(vec![coord("r_sat", 0.05)]);
+        let next = Residual::from_coords(vec![coord("r_sat", 0.12)]);
+        let strict = safestep_with(&prev, &next, &SafeStepPolicy::default());
+        assert_eq!(strict.decision, CorridorDecision::Derate);
+        assert_eq!(strict.breaches[0].kind, BreachKind::Lyapunov);
+
+        let policy = SafeStepPolicy { safe_interior_eps: 0.05 };
+        assert_eq!(safestep_with(&prev, &next, &policy).decision, CorridorDecision::Ok);
+    }
+
+    #[test]
+    fn safestep_with_stops_on_non_finite_telemetry() {
+        let prev = Residual::from_coords(vec![coord("r_sat", 0.3)]);
+        let next = Residual::from_coords(vec![coord("r_sat", f64::NAN)]);
+        let d = safestep_with(&prev, &next, &SafeStepPolicy::default());
+        assert_eq!(d.decision, CorridorDecision::Stop);
+        let kinds: Vec<BreachKind> = d.breaches.iter().map(|b| b.kind).collect();
+        assert_eq!(kinds, vec![BreachKind::Hard, BreachKind::Lyapunov]);
+
+        let bad_vt = Residual { vt: f64::NAN, coords: vec![coord("r_sat", 0.3)] };
+        let d = safestep_with(&prev, &bad_vt, &SafeStepPolicy::default());
+        assert_eq!(d.decision, CorridorDecision::Derate);
+        assert_eq!(d.offending(), vec!["V_t"]);
+    }
+
     #[test]
     fn ker_delta_respects_thresholds() {
         assert!(ker_delta(0.90, 0.88, 0.15, 0.93, 0.90, 0.13, 0.90, 0.89, 0.13));
         assert!(!ker_delta(0.90, 0.88, 0.15, 0.89, 0.90, 0.13, 0.90, 0.89, 0.13));
     }
+
+    #[test]
+    fn nan_triad_fails_every_ker_check() {
+        let nan = Triad { knowledge: f64::NAN, eco_impact: f64::NAN, risk_of_harm: f64::NAN };
+        let gate = KerGate { k_min: 0.9, e_min: 0.9, r_max: 0.13 };
+        assert!(!ker_delta(0.9, 0.9, 0.1, f64::NAN, f64::NAN, f64::NAN, 0.9, 0.9, 0.13));
+        assert!(!gate.admits(&nan));
+        assert_eq!(gate.misses(&nan), vec![KerAxis::K, KerAxis::E, KerAxis::R]);
+
+        let t = KerTransition {
+            prev: Triad { knowledge: 0.92, eco_impact: 0.91, risk_of_harm: 0.10 },
+            next: nan,
+        };
+        assert!(!t.passes(&gate));
+        let kinds: Vec<KerFailureKind> = t.evaluate(&gate).iter().map(|f| f.kind).collect();
+        assert_eq!(kinds, vec![KerFailureKind::Threshold; 3]);
+    }
+
+    #[test]
+    fn ker_transition_explains_failures() {
+        let gate = KerGate { k_min: 0.90, e_min: 0.89, r_max: 0.13 };
+        let t = KerTransition {
+            prev: Triad { knowledge: 0.92, eco_impact: 0.88, risk_of_harm: 0.12 },
+            next: Triad { knowledge: 0.91, eco_impact: 0.88, risk_of_harm: 0.14 },
+        };
+        let failures = t.evaluate(&gate);
+        let kinds: Vec<(Axis, KerFailureKind)> = failures.iter().map(|f| (f.axis, f.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Axis::K, KerFailureKind::Monotonicity),
+                (Axis::E, KerFailureKind::Threshold),
+                (Axis::R, KerFailureKind::Monotonicity),
+                (Axis::R, KerFailureKind::Threshold),
+            ]
+        );
+        assert_eq!(failures[0].to_string(), "K degraded from 0.920 to 0.910");
+        assert_eq!(gate.misses(&t.next), vec![KerAxis::E, KerAxis::R]);
+    }
 }
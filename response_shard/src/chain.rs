@@ -0,0 +1,276 @@
+//! Append-only, hash-linked `ResponseShard` history per (user DID, topic). [file:6]
+//!
+//! Each entry commits to its predecessor's hash, and an append is accepted
+//! only if the new shard `improves_over` the chain head, so an exported chain
+//! proves a research line only ever tightened.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::stamp::hexstamp;
+use crate::validation::ValidationErrors;
+use crate::ResponseShard;
+
+/// `prev_hash` of the first entry in every chain.
+pub const GENESIS_HASH: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Error)]
+pub enum ChainError {
+    #[error("shard belongs to ({user_did}, {topic}), not this chain")]
+    KeyMismatch { user_did: String, topic: String },
+    #[error("shard is invalid: {0}")]
+    Invalid(#[from] ValidationErrors),
+    #[error("entry {index} holds an invalid shard: {source}")]
+    InvalidEntry {
+        index: u64,
+        #[source]
+        source: ValidationErrors,
+    },
+    #[error("entry {index} does not tighten the chain head")]
+    NotTightening { index: u64 },
+    #[error("entry {index} does not link to its predecessor")]
+    BrokenLink { index: u64 },
+    #[error("entry {index} hash does not match its content")]
+    HashMismatch { index: u64 },
+    #[error("invalid chain export: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// (user DID, topic) pair a chain is keyed by.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ChainKey {
+    pub user_did: String,
+    pub topic: String,
+}
+
+impl ChainKey {
+    pub fn of(shard: &ResponseShard) -> Self {
+        Self {
+            user_did: shard.user_did.clone(),
+            topic: shard.topic.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainEntry {
+    pub index: u64,
+    pub prev_hash: String,
+    pub shard: ResponseShard,
+    pub entry_hash: String,
+}
+
+/// Hash of one entry: the hexstamp of its `ChainEntry` canonical encoding.
+pub fn entry_hash(prev_hash: &str, index: u64, shard: &ResponseShard) -> String {
+    hexstamp(&ChainEntry {
+        index,
+        prev_hash: prev_hash.to_string(),
+        shard: shard.clone(),
+        entry_hash: String::new(),
+    })
+}
+
+/// History of one research line. [file:6]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardChain {
+    pub key: ChainKey,
+    entries: Vec<ChainEntry>,
+}
+
+impl ShardChain {
+    pub fn new(user_did: impl Into<String>, topic: impl Into<String>) -> Self {
+        Self {
+            key: ChainKey {
+                user_did: user_did.into(),
+                topic: topic.into(),
+            },
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[ChainEntry] {
+        &self.entries
+    }
+
+    pub fn head(&self) -> Option<&ChainEntry> {
+        self.entries.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Append `shard` if it is valid, matches the key and tightens the head.
+    pub fn append(&mut self, shard: ResponseShard) -> Result<&ChainEntry, ChainError> {
+        if ChainKey::of(&shard) != self.key {
+            return Err(ChainError::KeyMismatch {
+                user_did: shard.user_did,
+                topic: shard.topic,
+            });
+        }
+        shard.validate()?;
+
+        let index = self.entries.len() as u64;
+        let prev_hash = match self.head() {
+            Some(head) => {
+                if !shard.improves_over(&head.shard) {
+                    return Err(ChainError::NotTightening { index });
+                }
+                head.entry_hash.clone()
+            }
+            None => GENESIS_HASH.to_string(),
+        };
+
+        let entry_hash = entry_hash(&prev_hash, index, &shard);
+        self.entries.push(ChainEntry {
+            index,
+            prev_hash,
+            shard,
+            entry_hash,
+        });
+        Ok(self.entries.last().expect("entry just pushed"))
+    }
+
+    /// Re-check every link, hash, shard and tightening step without trusting stored state.
+    pub fn verify(&self) -> Result<(), ChainError> {
+        let mut prev_hash = GENESIS_HASH;
+        for (i, entry) in self.entries.iter().enumerate() {
+            let index = i as u64;
+            if entry.index != index || entry.prev_hash != prev_hash {
+                return Err(ChainError::BrokenLink { index });
+            }
+            if ChainKey::of(&entry.shard) != self.key {
+                return Err(ChainError::KeyMismatch {
+                    user_did: entry.shard.user_did.clone(),
+                    topic: entry.shard.topic.clone(),
+                });
+            }
+            entry
+                .shard
+                .validate()
+                .map_err(|source| ChainError::InvalidEntry { index, source })?;
+            if entry_hash(&entry.prev_hash, index, &entry.shard) != entry.entry_hash {
+                return Err(ChainError::HashMismatch { index });
+            }
+            if i > 0 && !entry.shard.improves_over(&self.entries[i - 1].shard) {
+                return Err(ChainError::NotTightening { index });
+            }
+            prev_hash = &entry.entry_hash;
+        }
+        Ok(())
+    }
+
+    /// Export as JSON for offline verification.
+    pub fn export_json(&self) -> String {
+        serde_json::to_string(self).expect("ShardChain serializes to JSON")
+    }
+
+    /// Import an exported chain, rejecting it unless `verify` passes.
+    pub fn import_json(input: &str) -> Result<Self, ChainError> {
+        let chain: Self = serde_json::from_str(input)?;
+        chain.verify()?;
+        Ok(chain)
+    }
+}
+
+/// All chains, one per (user DID, topic).
+#[derive(Debug, Clone, Default)]
+pub struct ShardChains {
+    chains: BTreeMap<ChainKey, ShardChain>,
+}
+
+impl ShardChains {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route `shard` to its chain, creating the chain on first use.
+    pub fn append(&mut self, shard: ResponseShard) -> Result<&ChainEntry, ChainError> {
+        let key = ChainKey::of(&shard);
+        self.chains
+            .entry(key.clone())
+            .or_insert_with(|| ShardChain::new(key.user_did, key.topic))
+            .append(shard)
+    }
+
+    pub fn get(&self, user_did: &str, topic: &str) -> Option<&ShardChain> {
+        self.chains.get(&ChainKey {
+            user_did: user_did.into(),
+            topic: topic.into(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ShardChain> {
+        self.chains.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, Triad};
+
+    fn shard(k: f64, vt: f64) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual { vt, coords: vec![] },
+            evidence: vec![],
+            corridor_tags: vec!["mar".into()],
+        }
+    }
+
+    #[test]
+    fn append_links_and_rejects_loosening() {
+        let mut chain = ShardChain::new("bostrom18...", "phoenix-mar-sat");
+        let first = chain.append(shard(0.90, 0.25)).unwrap().entry_hash.clone();
+        let second = chain.append(shard(0.93, 0.22)).unwrap();
+        assert_eq!(second.prev_hash, first);
+        assert_eq!(hexstamp(second), second.entry_hash);
+
+        assert!(matches!(
+            chain.append(shard(0.91, 0.20)),
+            Err(ChainError::NotTightening { index: 2 })
+        ));
+        assert_eq!(chain.len(), 2);
+    }
+
+    #[test]
+    fn export_verifies_and_detects_tampering() {
+        let mut chains = ShardChains::new();
+        chains.append(shard(0.90, 0.25)).unwrap();
+        chains.append(shard(0.93, 0.22)).unwrap();
+        let exported = chains.get("bostrom18...", "phoenix-mar-sat").unwrap().export_json();
+
+        let chain = ShardChain::import_json(&exported).unwrap();
+        assert_eq!(chain.len(), 2);
+
+        let tampered = exported.replacen("0.93", "0.99", 1);
+        assert!(matches!(
+            ShardChain::import_json(&tampered),
+            Err(ChainError::HashMismatch { index: 1 })
+        ));
+    }
+
+    #[test]
+    fn import_rejects_out_of_range_shards_with_valid_hashes() {
+        let mut chain = ShardChain::new("bostrom18...", "phoenix-mar-sat");
+        chain.append(shard(0.90, 0.25)).unwrap();
+        let entry = &mut chain.entries[0];
+        entry.shard.triad.knowledge = 5.0;
+        entry.shard.triad.eco_impact = -3.0;
+        entry.entry_hash = entry_hash(&entry.prev_hash, 0, &entry.shard);
+
+        assert!(matches!(
+            ShardChain::import_json(&chain.export_json()),
+            Err(ChainError::InvalidEntry { index: 0, .. })
+        ));
+    }
+}
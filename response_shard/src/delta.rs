@@ -0,0 +1,336 @@
+//! Per-axis shard deltas with Pareto classification. [file:6]
+//!
+//! The Pareto verdict covers K/E/R/V_t and every r_x matched by `var_id`, so
+//! it is stricter than `improves_over`: a worse r_x, a dropped r_x or a NaN
+//! move all count as worse. The delta keeps each axis so a reviewer can see
+//! which one moved.
+
+use std::fmt;
+
+use crate::ResponseShard;
+
+/// Axes compared by `improves_over`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    K,
+    E,
+    R,
+    Vt,
+}
+
+impl Axis {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Axis::K => "K",
+            Axis::E => "E",
+            Axis::R => "R",
+            Axis::Vt => "V_t",
+        }
+    }
+
+    /// K and E should rise; R and V_t should fall.
+    fn higher_is_better(&self) -> bool {
+        matches!(self, Axis::K | Axis::E)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Better,
+    Worse,
+    Unchanged,
+}
+
+fn classify_change(delta: f64, eps: f64, higher_is_better: bool) -> Change {
+    if delta.is_nan() {
+        Change::Worse
+    } else if delta.abs() <= eps {
+        Change::Unchanged
+    } else if (delta > 0.0) == higher_is_better {
+        Change::Better
+    } else {
+        Change::Worse
+    }
+}
+
+/// Tolerances below which a move counts as unchanged; zero by default.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DeltaEpsilons {
+    pub k: f64,
+    pub e: f64,
+    pub r: f64,
+    pub vt: f64,
+    pub rx: f64,
+}
+
+impl DeltaEpsilons {
+    /// The same tolerance on every axis.
+    pub fn uniform(eps: f64) -> Self {
+        Self {
+            k: eps,
+            e: eps,
+            r: eps,
+            vt: eps,
+            rx: eps,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisDelta {
+    pub axis: Axis,
+    pub prev: f64,
+    pub next: f64,
+    /// `next - prev`.
+    pub delta: f64,
+    pub change: Change,
+}
+
+/// One r_x matched by `var_id`; `None` on the side where it is absent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordDelta {
+    pub var_id: String,
+    pub prev: Option<f64>,
+    pub next: Option<f64>,
+    pub change: Change,
+}
+
+impl CoordDelta {
+    /// `next - prev` when the coordinate exists on both sides.
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.next? - self.prev?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pareto {
+    /// No axis or r_x worse and at least one better.
+    Dominates,
+    /// No axis or r_x better and at least one worse.
+    Dominated,
+    /// Some better, some worse.
+    Incomparable,
+    /// Every axis and r_x within its epsilon.
+    Equal,
+}
+
+/// Signed movement from a previous shard to this one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardDelta {
+    pub axes: [AxisDelta; 4],
+    /// Coordinates of the newer shard in its order, then ones it dropped.
+    pub coords: Vec<CoordDelta>,
+    pub pareto: Pareto,
+}
+
+impl ShardDelta {
+    pub fn axis(&self, axis: Axis) -> &AxisDelta {
+        self.axes.iter().find(|a| a.axis == axis).expect("all axes present")
+    }
+
+    pub fn worsened(&self) -> impl Iterator<Item = &AxisDelta> {
+        self.axes.iter().filter(|a| a.change == Change::Worse)
+    }
+
+    /// Coordinates that rose past epsilon or were dropped.
+    pub fn worsened_coords(&self) -> impl Iterator<Item = &CoordDelta> {
+        self.coords.iter().filter(|c| c.change == Change::Worse)
+    }
+
+    /// One line per review comment, e.g. `Incomparable: K +0.030 (better), V_t +0.010 (worse)`.
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn fmt_change(change: Change) -> &'static str {
+    match change {
+        Change::Better => "better",
+        Change::Worse => "worse",
+        Change::Unchanged => "unchanged",
+    }
+}
+
+impl fmt::Display for ShardDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}:", self.pareto)?;
+        let axes: Vec<String> = self
+            .axes
+            .iter()
+            .map(|a| format!("{} {:+.3} ({})", a.axis.as_str(), a.delta, fmt_change(a.change)))
+            .collect();
+        write!(f, " {}", axes.join(", "))?;
+
+        let moved: Vec<String> = self
+            .coords
+            .iter()
+            .filter(|c| c.change != Change::Unchanged || c.prev.is_none() || c.next.is_none())
+            .map(|c| match (c.prev, c.next) {
+                (Some(p), Some(n)) => format!("{} {:+.3} ({})", c.var_id, n - p, fmt_change(c.change)),
+                (None, Some(_)) => format!("{} added", c.var_id),
+                _ => format!("{} removed", c.var_id),
+            })
+            .collect();
+        if !moved.is_empty() {
+            write!(f, "; {}", moved.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl ResponseShard {
+    /// Delta against `previous` with zero tolerances.
+    pub fn delta(&self, previous: &ResponseShard) -> ShardDelta {
+        self.delta_with(previous, &DeltaEpsilons::default())
+    }
+
+    /// Delta against `previous`, treating moves within `eps` as unchanged.
+    pub fn delta_with(&self, previous: &ResponseShard, eps: &DeltaEpsilons) -> ShardDelta {
+        let axis = |axis: Axis, prev: f64, next: f64, eps: f64| {
+            let delta = next - prev;
+            AxisDelta {
+                axis,
+                prev,
+                next,
+                delta,
+                change: classify_change(delta, eps, axis.higher_is_better()),
+            }
+        };
+        let axes = [
+            axis(Axis::K, previous.triad.knowledge, self.triad.knowledge, eps.k),
+            axis(Axis::E, previous.triad.eco_impact, self.triad.eco_impact, eps.e),
+            axis(Axis::R, previous.triad.risk_of_harm, self.triad.risk_of_harm, eps.r),
+            axis(Axis::Vt, previous.residual.vt, self.residual.vt, eps.vt),
+        ];
+
+        let mut coords: Vec<CoordDelta> = self
+            .residual
+            .coords
+            .iter()
+            .map(|c| {
+                let prev = previous.residual.coords.iter().find(|p| p.var_id == c.var_id).map(|p| p.value);
+                let change = match prev {
+                    Some(p) => classify_change(c.value - p, eps.rx, false),
+                    None => Change::Unchanged,
+                };
+                CoordDelta {
+                    var_id: c.var_id.clone(),
+                    prev,
+                    next: Some(c.value),
+                    change,
+                }
+            })
+            .collect();
+        coords.extend(
+            previous
+                .residual
+                .coords
+                .iter()
+                .filter(|p| !self.residual.coords.iter().any(|c| c.var_id == p.var_id))
+                .map(|p| CoordDelta {
+                    var_id: p.var_id.clone(),
+                    prev: Some(p.value),
+                    next: None,
+                    change: Change::Worse,
+                }),
+        );
+
+        let changes = || axes.iter().map(|a| a.change).chain(coords.iter().map(|c| c.change));
+        let better = changes().any(|c| c == Change::Better);
+        let worse = changes().any(|c| c == Change::Worse);
+        let pareto = match (better, worse) {
+            (true, false) => Pareto::Dominates,
+            (false, true) => Pareto::Dominated,
+            (true, true) => Pareto::Incomparable,
+            (false, false) => Pareto::Equal,
+        };
+
+        ShardDelta { axes, coords, pareto }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, RiskCoord, Triad};
+
+    fn shard(k: f64, vt: f64, coords: &[(&str, f64)]) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual {
+                vt,
+                coords: coords
+                    .iter()
+                    .map(|(id, v)| RiskCoord {
+                        var_id: id.to_string(),
+                        value: *v,
+                        safe: 0.0,
+                        gold: 0.7,
+                        hard: 1.0,
+                        weight: 0.5,
+                    })
+                    .collect(),
+            },
+            evidence: vec![],
+            corridor_tags: vec![],
+        }
+    }
+
+    #[test]
+    fn classifies_pairs() {
+        let prev = shard(0.90, 0.25, &[]);
+        assert_eq!(shard(0.93, 0.22, &[]).delta(&prev).pareto, Pareto::Dominates);
+        assert_eq!(shard(0.88, 0.25, &[]).delta(&prev).pareto, Pareto::Dominated);
+        assert_eq!(shard(0.90, 0.25, &[]).delta(&prev).pareto, Pareto::Equal);
+
+        let mixed = shard(0.93, 0.26, &[]).delta(&prev);
+        assert_eq!(mixed.pareto, Pareto::Incomparable);
+        assert_eq!(mixed.worsened().next().unwrap().axis, Axis::Vt);
+    }
+
+    #[test]
+    fn epsilons_absorb_noise() {
+        let prev = shard(0.90, 0.25, &[]);
+        let next = shard(0.93, 0.2505, &[]);
+        assert_eq!(next.delta(&prev).pareto, Pareto::Incomparable);
+        let eps = DeltaEpsilons { vt: 1e-3, ..DeltaEpsilons::default() };
+        assert_eq!(next.delta_with(&prev, &eps).pareto, Pareto::Dominates);
+    }
+
+    #[test]
+    fn matches_coords_by_var_id() {
+        let prev = shard(0.90, 0.25, &[("r_sat", 0.3), ("r_pfas", 0.2)]);
+        let next = shard(0.90, 0.25, &[("r_temp", 0.1), ("r_sat", 0.4)]);
+        let d = next.delta(&prev);
+
+        assert_eq!(d.coords[0].var_id, "r_temp");
+        assert_eq!(d.coords[0].prev, None);
+        assert_eq!(d.coords[1].change, Change::Worse);
+        assert!((d.coords[1].delta().unwrap() - 0.1).abs() < 1e-12);
+        assert_eq!(d.coords[2].next, None);
+        assert_eq!(d.coords[2].change, Change::Worse);
+        assert_eq!(d.worsened_coords().count(), 2);
+
+        let summary = d.summary();
+        assert!(summary.starts_with("Dominated: K +0.000 (unchanged)"));
+        assert!(summary.contains("r_sat +0.100 (worse)"));
+        assert!(summary.contains("r_temp added"));
+        assert!(summary.contains("r_pfas removed"));
+    }
+
+    #[test]
+    fn coords_and_nan_feed_the_verdict() {
+        let prev = shard(0.90, 0.25, &[("r_sat", 0.3)]);
+        let sat_worse = shard(0.93, 0.25, &[("r_sat", 0.4)]).delta(&prev);
+        assert_eq!(sat_worse.pareto, Pareto::Incomparable);
+        let sat_better = shard(0.90, 0.25, &[("r_sat", 0.2)]).delta(&prev);
+        assert_eq!(sat_better.pareto, Pareto::Dominates);
+
+        let nan = shard(0.90, f64::NAN, &[("r_sat", f64::NAN)]).delta(&prev);
+        assert_eq!(nan.axis(Axis::Vt).change, Change::Worse);
+        assert_eq!(nan.coords[0].change, Change::Worse);
+        assert_eq!(nan.pareto, Pareto::Dominated);
+    }
+}
@@ -0,0 +1,216 @@
+//! One evaluation path for the ALN invariants, advisory or hard. [file:6][file:7]
+//!
+//! Response-level K/E/R and V_t are advisory: a failed invariant becomes a
+//! warning plus a down-rank score. Deployment gates are hard: the same
+//! reasons come back as a blocking error. The checks are the registry's
+//! `aln.core` invariants, so `enforce` and `InvariantRegistry::aln_core`
+//! never disagree about a step.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::aln_invariants::{KerFailure, KerGate, SafeStepPolicy};
+use crate::invariants::{KerDelta, NoCorridorNoBuild, SafeStep};
+use crate::ResponseShard;
+
+/// How failed invariants are surfaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnforcementMode {
+    /// Conversational pipelines: warn and down-rank.
+    Advisory,
+    /// Deployment gates: block.
+    Hard,
+}
+
+/// Number of invariants evaluated; the down-rank score is failed invariants over this.
+const INVARIANT_COUNT: usize = 3;
+
+/// Why an invariant did not hold.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvariantViolation {
+    /// no_corridor_no_build: required variables without a corridor row.
+    NoCorridor { missing: Vec<String> },
+    /// safestep: coordinates past their own hard limit (or below 0).
+    HardLimit { var_ids: Vec<String> },
+    /// safestep: coordinates past their gold band but within hard.
+    GoldBand { var_ids: Vec<String> },
+    /// safestep: V_t rose outside the safe interior.
+    VtIncreased { prev: f64, next: f64 },
+    /// ker_delta: K/E/R degraded or missed the gate.
+    KerDelta { failures: Vec<KerFailure> },
+}
+
+impl InvariantViolation {
+    /// ALN name of the invariant that failed.
+    pub fn invariant(&self) -> &'static str {
+        match self {
+            InvariantViolation::NoCorridor { .. } => "no_corridor_no_build",
+            InvariantViolation::HardLimit { .. }
+            | InvariantViolation::GoldBand { .. }
+            | InvariantViolation::VtIncreased { .. } => "safestep",
+            InvariantViolation::KerDelta { .. } => "ker_delta",
+        }
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvariantViolation::NoCorridor { missing } => {
+                write!(f, "no_corridor_no_build: no corridor for {}", missing.join(", "))
+            }
+            InvariantViolation::HardLimit { var_ids } => {
+                write!(f, "safestep: hard limit crossed by {}", var_ids.join(", "))
+            }
+            InvariantViolation::GoldBand { var_ids } => {
+                write!(f, "safestep: gold band exceeded by {}", var_ids.join(", "))
+            }
+            InvariantViolation::VtIncreased { prev, next } => {
+                write!(f, "safestep: V_t rose from {:.3} to {:.3}", prev, next)
+            }
+            InvariantViolation::KerDelta { failures } => {
+                let reasons: Vec<String> = failures.iter().map(ToString::to_string).collect();
+                write!(f, "ker_delta: {}", reasons.join(", "))
+            }
+        }
+    }
+}
+
+fn join_reasons(reasons: &[InvariantViolation]) -> String {
+    reasons.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EnforcementError {
+    #[error("blocked by hard invariants: {}", join_reasons(.reasons))]
+    Blocked { reasons: Vec<InvariantViolation> },
+}
+
+/// Everything the invariants look at for one prev → next step.
+#[derive(Debug, Clone, Copy)]
+pub struct InvariantContext<'a> {
+    pub required_vars: &'a [&'a str],
+    pub prev: &'a ResponseShard,
+    pub next: &'a ResponseShard,
+    pub policy: SafeStepPolicy,
+    pub gate: KerGate,
+}
+
+/// Result of an evaluation that did not block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvariantOutcome {
+    pub mode: EnforcementMode,
+    /// Empty in `Hard` mode, since any violation blocks.
+    pub warnings: Vec<InvariantViolation>,
+    /// Share of invariants that failed, in [0,1]; 0 means no down-ranking.
+    pub down_rank: f64,
+}
+
+impl InvariantOutcome {
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Run every invariant and collect the reasons each one failed.
+pub fn violations(ctx: &InvariantContext<'_>) -> Vec<InvariantViolation> {
+    let mut out = Vec::new();
+    out.extend(NoCorridorNoBuild::new(ctx.required_vars).violation(ctx.next));
+    out.extend(SafeStep { policy: ctx.policy }.violations(ctx.prev, ctx.next));
+    out.extend(KerDelta { gate: ctx.gate }.violation(ctx.prev, ctx.next));
+    out
+}
+
+/// Evaluate the invariants under `mode`: advisory warns and down-ranks, hard blocks.
+pub fn enforce(ctx: &InvariantContext<'_>, mode: EnforcementMode) -> Result<InvariantOutcome, EnforcementError> {
+    let reasons = violations(ctx);
+    match mode {
+        EnforcementMode::Hard if !reasons.is_empty() => Err(EnforcementError::Blocked { reasons }),
+        _ => {
+            let mut failed: Vec<&str> = reasons.iter().map(InvariantViolation::invariant).collect();
+            failed.dedup();
+            let down_rank = failed.len() as f64 / INVARIANT_COUNT as f64;
+            Ok(InvariantOutcome {
+                mode,
+                warnings: reasons,
+                down_rank,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, RiskCoord, Triad};
+
+    fn shard(k: f64, vt: f64, coords: &[(&str, f64)]) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual {
+                vt,
+                coords: coords
+                    .iter()
+                    .map(|(id, v)| RiskCoord {
+                        var_id: id.to_string(),
+                        value: *v,
+                        safe: 0.0,
+                        gold: 0.7,
+                        hard: 1.0,
+                        weight: 0.5,
+                    })
+                    .collect(),
+            },
+            evidence: vec![],
+            corridor_tags: vec![],
+        }
+    }
+
+    fn ctx<'a>(prev: &'a ResponseShard, next: &'a ResponseShard) -> InvariantContext<'a> {
+        InvariantContext {
+            required_vars: &["r_sat", "r_pfas"],
+            prev,
+            next,
+            policy: SafeStepPolicy::default(),
+            gate: KerGate { k_min: 0.90, e_min: 0.85, r_max: 0.15 },
+        }
+    }
+
+    #[test]
+    fn same_reasons_in_both_modes() {
+        let prev = shard(0.92, 0.20, &[("r_sat", 0.3), ("r_pfas", 0.1)]);
+        let next = shard(0.91, 0.25, &[("r_sat", 0.3)]);
+
+        let advisory = enforce(&ctx(&prev, &next), EnforcementMode::Advisory).unwrap();
+        assert_eq!(advisory.warnings.len(), 3);
+        assert_eq!(advisory.down_rank, 1.0);
+        assert_eq!(
+            advisory.warnings[0],
+            InvariantViolation::NoCorridor { missing: vec!["r_pfas".into()] }
+        );
+
+        let EnforcementError::Blocked { reasons } = enforce(&ctx(&prev, &next), EnforcementMode::Hard).unwrap_err();
+        assert_eq!(reasons, advisory.warnings);
+    }
+
+    #[test]
+    fn hard_limit_warns_and_clean_step_passes() {
+        let prev = shard(0.92, 0.20, &[("r_sat", 0.3), ("r_pfas", 0.1)]);
+        let next = shard(0.93, 0.18, &[("r_sat", 0.8), ("r_pfas", 1.2)]);
+        let advisory = enforce(&ctx(&prev, &next), EnforcementMode::Advisory).unwrap();
+        assert_eq!(
+            advisory.warnings,
+            vec![
+                InvariantViolation::HardLimit { var_ids: vec!["r_pfas".into()] },
+                InvariantViolation::GoldBand { var_ids: vec!["r_sat".into()] },
+            ]
+        );
+        assert!((advisory.down_rank - 1.0 / 3.0).abs() < 1e-12);
+
+        let ok = shard(0.93, 0.18, &[("r_sat", 0.2), ("r_pfas", 0.1)]);
+        assert!(enforce(&ctx(&prev, &ok), EnforcementMode::Hard).unwrap().is_clean());
+    }
+}
@@ -0,0 +1,276 @@
+//! Typed evidence references for shards. [file:6][file:11]
+//!
+//! Evidence must point at equations, corridor definitions, shard schemas or
+//! measured data, never free-floating claims. Each reference has a compact
+//! string form used by `evidencestrings` on the wire:
+//!
+//! - `eq:<id>`
+//! - `corridor:<particle>/<var_id>`
+//! - `shard:<schema>@<hexstamp>`
+//! - `pilot:<var_id>=<value>[<units>]`
+//! - `band:<var_id>=<safe>/<gold>/<hard>[<units>]`
+//! - `lab:<lab_id>/<report_id>`
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::stamp::is_well_formed_hexstamp;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum EvidenceError {
+    #[error("evidence `{0}` has no recognised `kind:` prefix")]
+    UnknownKind(String),
+    #[error("evidence `{input}` is malformed: {reason}")]
+    Malformed { input: String, reason: String },
+}
+
+/// One reference backing a shard's K/E/R. [file:6]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EvidenceRef {
+    /// An equation or kernel from the ecosafety documents, e.g. `K=N_backed/N_critical`.
+    Equation { id: String },
+    /// A corridor row: the particle that defines it and the variable it bounds.
+    CorridorRow { particle: String, var_id: String },
+    /// Another shard, pinned by schema and content hexstamp.
+    ShardRef { schema: String, hexstamp: String },
+    /// A measured value from a pilot, with units.
+    PilotMeasurement { var_id: String, value: f64, units: String },
+    /// A laboratory report by lab and report id.
+    LabReport { lab_id: String, report_id: String },
+    /// Physical bands a coordinate was normalized against; does not back K.
+    CorridorBand { var_id: String, units: String, safe: f64, gold: f64, hard: f64 },
+}
+
+/// Characters that would break the string form or the `;`-separated wire list.
+fn clean(part: &str) -> bool {
+    !part.is_empty() && part.trim() == part && !part.chars().any(|c| matches!(c, ';' | '[' | ']') || c.is_control())
+}
+
+impl EvidenceRef {
+    pub fn equation(id: impl Into<String>) -> Self {
+        EvidenceRef::Equation { id: id.into() }
+    }
+
+    pub fn corridor_row(particle: impl Into<String>, var_id: impl Into<String>) -> Self {
+        EvidenceRef::CorridorRow {
+            particle: particle.into(),
+            var_id: var_id.into(),
+        }
+    }
+
+    pub fn pilot(var_id: impl Into<String>, value: f64, units: impl Into<String>) -> Self {
+        EvidenceRef::PilotMeasurement {
+            var_id: var_id.into(),
+            value,
+            units: units.into(),
+        }
+    }
+
+    pub fn band(var_id: impl Into<String>, units: impl Into<String>, safe: f64, gold: f64, hard: f64) -> Self {
+        EvidenceRef::CorridorBand {
+            var_id: var_id.into(),
+            units: units.into(),
+            safe,
+            gold,
+            hard,
+        }
+    }
+
+    /// Corridor variable this reference backs, if it is a corridor row.
+    pub fn corridor_var(&self) -> Option<&str> {
+        match self {
+            EvidenceRef::CorridorRow { var_id, .. } => Some(var_id),
+            _ => None,
+        }
+    }
+
+    /// Check field contents; returns the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let check = |name: &str, part: &str| -> Result<(), String> {
+            if clean(part) {
+                Ok(())
+            } else {
+                Err(format!("{} `{}` is empty or contains reserved characters", name, part))
+            }
+        };
+        match self {
+            EvidenceRef::Equation { id } => check("equation id", id),
+            EvidenceRef::CorridorRow { particle, var_id } => {
+                check("particle", particle)?;
+                check("var_id", var_id)?;
+                if particle.contains('/') {
+                    return Err(format!("particle `{}` contains `/`", particle));
+                }
+                Ok(())
+            }
+            EvidenceRef::ShardRef { schema, hexstamp } => {
+                check("schema", schema)?;
+                if schema.contains('@') {
+                    return Err(format!("schema `{}` contains `@`", schema));
+                }
+                if !is_well_formed_hexstamp(hexstamp) {
+                    return Err(format!("hexstamp `{}` is not 0x-prefixed SHA-256 hex", hexstamp));
+                }
+                Ok(())
+            }
+            EvidenceRef::PilotMeasurement { var_id, value, units } => {
+                check("var_id", var_id)?;
+                check("units", units)?;
+                if var_id.contains('=') {
+                    return Err(format!("var_id `{}` contains `=`", var_id));
+                }
+                if !value.is_finite() {
+                    return Err(format!("measurement {} is not finite", value));
+                }
+                Ok(())
+            }
+            EvidenceRef::LabReport { lab_id, report_id } => {
+                check("lab_id", lab_id)?;
+                check("report_id", report_id)?;
+                if lab_id.contains('/') {
+                    return Err(format!("lab_id `{}` contains `/`", lab_id));
+                }
+                Ok(())
+            }
+            EvidenceRef::CorridorBand { var_id, units, safe, gold, hard } => {
+                check("var_id", var_id)?;
+                check("units", units)?;
+                if var_id.contains('=') {
+                    return Err(format!("var_id `{}` contains `=`", var_id));
+                }
+                if ![safe, gold, hard].iter().all(|v| v.is_finite()) {
+                    return Err(format!("band {}/{}/{} is not finite", safe, gold, hard));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for EvidenceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvidenceRef::Equation { id } => write!(f, "eq:{}", id),
+            EvidenceRef::CorridorRow { particle, var_id } => write!(f, "corridor:{}/{}", particle, var_id),
+            EvidenceRef::ShardRef { schema, hexstamp } => write!(f, "shard:{}@{}", schema, hexstamp),
+            EvidenceRef::PilotMeasurement { var_id, value, units } => write!(f, "pilot:{}={}[{}]", var_id, value, units),
+            EvidenceRef::LabReport { lab_id, report_id } => write!(f, "lab:{}/{}", lab_id, report_id),
+            EvidenceRef::CorridorBand { var_id, units, safe, gold, hard } => {
+                write!(f, "band:{}={}/{}/{}[{}]", var_id, safe, gold, hard, units)
+            }
+        }
+    }
+}
+
+impl FromStr for EvidenceRef {
+    type Err = EvidenceError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let malformed = |reason: &str| EvidenceError::Malformed {
+            input: input.to_string(),
+            reason: reason.to_string(),
+        };
+        let (kind, body) = input
+            .split_once(':')
+            .ok_or_else(|| EvidenceError::UnknownKind(input.to_string()))?;
+
+        let parsed = match kind {
+            "eq" => EvidenceRef::equation(body),
+            "corridor" => {
+                let (particle, var_id) = body.split_once('/').ok_or_else(|| malformed("expected `<particle>/<var_id>`"))?;
+                EvidenceRef::corridor_row(particle, var_id)
+            }
+            "shard" => {
+                let (schema, hexstamp) = body.rsplit_once('@').ok_or_else(|| malformed("expected `<schema>@<hexstamp>`"))?;
+                EvidenceRef::ShardRef {
+                    schema: schema.into(),
+                    hexstamp: hexstamp.into(),
+                }
+            }
+            "pilot" => {
+                let (var_id, rest) = body.split_once('=').ok_or_else(|| malformed("expected `<var_id>=<value>[<units>]`"))?;
+                let (value, units) = rest
+                    .strip_suffix(']')
+                    .and_then(|r| r.split_once('['))
+                    .ok_or_else(|| malformed("expected `<value>[<units>]`"))?;
+                let value = value.parse::<f64>().map_err(|_| malformed("value is not a number"))?;
+                EvidenceRef::pilot(var_id, value, units)
+            }
+            "lab" => {
+                let (lab_id, report_id) = body.split_once('/').ok_or_else(|| malformed("expected `<lab_id>/<report_id>`"))?;
+                EvidenceRef::LabReport {
+                    lab_id: lab_id.into(),
+                    report_id: report_id.into(),
+                }
+            }
+            "band" => {
+                let (var_id, rest) = body
+                    .split_once('=')
+                    .ok_or_else(|| malformed("expected `<var_id>=<safe>/<gold>/<hard>[<units>]`"))?;
+                let (bands, units) = rest
+                    .strip_suffix(']')
+                    .and_then(|r| r.split_once('['))
+                    .ok_or_else(|| malformed("expected `<safe>/<gold>/<hard>[<units>]`"))?;
+                let bands = bands
+                    .split('/')
+                    .map(|v| v.parse::<f64>().map_err(|_| malformed("band is not a number")))
+                    .collect::<Result<Vec<f64>, _>>()?;
+                let [safe, gold, hard] = bands[..] else {
+                    return Err(malformed("expected three bands"));
+                };
+                EvidenceRef::band(var_id, units, safe, gold, hard)
+            }
+            _ => return Err(EvidenceError::UnknownKind(input.to_string())),
+        };
+        parsed.validate().map_err(|reason| malformed(&reason))?;
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_form_round_trips() {
+        let refs = vec![
+            EvidenceRef::equation("K=N_backed/N_critical"),
+            EvidenceRef::corridor_row("mar.satcell.corridors.v1", "r_pfas"),
+            EvidenceRef::ShardRef {
+                schema: "response.shard.v1".into(),
+                hexstamp: format!("0x{}", "ab".repeat(32)),
+            },
+            EvidenceRef::pilot("nitrate_in", 10.5, "mg/L"),
+            EvidenceRef::LabReport {
+                lab_id: "asu-wet-lab".into(),
+                report_id: "2026-014".into(),
+            },
+            EvidenceRef::band("r_redox", "mV", -150.0, -100.0, -20.0),
+        ];
+        for r in refs {
+            let s = r.to_string();
+            assert_eq!(s.parse::<EvidenceRef>().unwrap(), r, "{}", s);
+        }
+        assert_eq!(
+            EvidenceRef::pilot("nitrate_in", 10.0, "mg/L").to_string(),
+            "pilot:nitrate_in=10[mg/L]"
+        );
+    }
+
+    #[test]
+    fn rejects_free_floating_and_malformed() {
+        assert!(matches!(
+            "nitrate_in_mg_l=10".parse::<EvidenceRef>(),
+            Err(EvidenceError::UnknownKind(_))
+        ));
+        assert!(matches!(
+            "shard:response.shard.v1@0x4a3b2c1d9e8f7g6h".parse::<EvidenceRef>(),
+            Err(EvidenceError::Malformed { .. })
+        ));
+        assert!(EvidenceRef::pilot("pfas_out", f64::NAN, "ng/L").validate().is_err());
+    }
+}
@@ -0,0 +1,487 @@
+//! Declarative invariant registry with batch evaluation. [file:7]
+//!
+//! Domains register `Invariant`s by name instead of forking gate functions,
+//! group them into named sets, and run a set against one shard or a
+//! prev → next pair. Each result carries the invariant's stamp so a report
+//! pins exactly which definition ran. The three core invariants are the same
+//! checks `enforcement::enforce` runs, all `Hard`; response-level callers get
+//! advisory handling from `EnforcementMode::Advisory`, not a weaker severity.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::aln_invariants::{no_corridor_no_build, safestep_with, BreachKind, KerGate, KerTransition, SafeStepPolicy};
+use crate::enforcement::InvariantViolation;
+use crate::stamp::{hexstamp, CanonicalEncode, CanonicalEncoder};
+use crate::ResponseShard;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RegistryError {
+    #[error("invariant `{0}` is already registered")]
+    Duplicate(String),
+    #[error("unknown invariant `{0}`")]
+    UnknownInvariant(String),
+    #[error("unknown invariant set `{0}`")]
+    UnknownSet(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Failure warns; response-level checks.
+    Advisory,
+    /// Failure blocks; deployment gates.
+    Hard,
+}
+
+/// What an invariant is evaluated on: one shard, or a step from `prev`.
+#[derive(Debug, Clone, Copy)]
+pub struct ShardContext<'a> {
+    pub prev: Option<&'a ResponseShard>,
+    pub next: &'a ResponseShard,
+}
+
+impl<'a> ShardContext<'a> {
+    pub fn single(shard: &'a ResponseShard) -> Self {
+        Self { prev: None, next: shard }
+    }
+
+    pub fn pair(prev: &'a ResponseShard, next: &'a ResponseShard) -> Self {
+        Self { prev: Some(prev), next }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    /// The invariant needs a shard pair and got one shard.
+    Skipped(String),
+}
+
+pub trait Invariant {
+    fn name(&self) -> &str;
+
+    /// Content stamp of the definition, including its parameters.
+    fn stamp(&self) -> String;
+
+    fn severity(&self) -> Severity;
+
+    fn evaluate(&self, ctx: &ShardContext<'_>) -> Outcome;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvariantResult {
+    pub name: String,
+    pub stamp: String,
+    pub severity: Severity,
+    pub outcome: Outcome,
+}
+
+/// Every invariant's outcome from one run, in set order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvariantReport {
+    pub set: String,
+    pub results: Vec<InvariantResult>,
+}
+
+impl InvariantReport {
+    fn failed(&self, severity: Severity) -> impl Iterator<Item = &InvariantResult> {
+        self.results
+            .iter()
+            .filter(move |r| r.severity == severity && matches!(r.outcome, Outcome::Fail(_)))
+    }
+
+    /// Hard invariants that failed.
+    pub fn blocking(&self) -> impl Iterator<Item = &InvariantResult> {
+        self.failed(Severity::Hard)
+    }
+
+    /// Advisory invariants that failed.
+    pub fn warnings(&self) -> impl Iterator<Item = &InvariantResult> {
+        self.failed(Severity::Advisory)
+    }
+
+    /// True when no hard invariant failed.
+    pub fn passes(&self) -> bool {
+        self.blocking().next().is_none()
+    }
+}
+
+#[derive(Default)]
+pub struct InvariantRegistry {
+    invariants: BTreeMap<String, Box<dyn Invariant>>,
+    sets: BTreeMap<String, Vec<String>>,
+}
+
+impl InvariantRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry holding the three ALN invariants as set `aln.core`.
+    pub fn aln_core(required_vars: &[&str], policy: SafeStepPolicy, gate: KerGate) -> Self {
+        let mut registry = Self::new();
+        let core: Vec<Box<dyn Invariant>> = vec![
+            Box::new(NoCorridorNoBuild::new(required_vars)),
+            Box::new(SafeStep { policy }),
+            Box::new(KerDelta { gate }),
+        ];
+        let names: Vec<String> = core.iter().map(|i| i.name().to_string()).collect();
+        for invariant in core {
+            registry.register(invariant).expect("core names are distinct");
+        }
+        registry.define_set("aln.core", names).expect("core invariants are registered");
+        registry
+    }
+
+    pub fn register(&mut self, invariant: Box<dyn Invariant>) -> Result<(), RegistryError> {
+        let name = invariant.name().to_string();
+        if self.invariants.contains_key(&name) {
+            return Err(RegistryError::Duplicate(name));
+        }
+        self.invariants.insert(name, invariant);
+        Ok(())
+    }
+
+    /// Name an ordered group of registered invariants; redefining a set replaces it.
+    pub fn define_set<I, S>(&mut self, set: &str, names: I) -> Result<(), RegistryError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        if let Some(unknown) = names.iter().find(|n| !self.invariants.contains_key(*n)) {
+            return Err(RegistryError::UnknownInvariant(unknown.clone()));
+        }
+        self.sets.insert(set.to_string(), names);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Invariant> {
+        self.invariants.get(name).map(|b| b.as_ref())
+    }
+
+    pub fn run_set(&self, set: &str, ctx: &ShardContext<'_>) -> Result<InvariantReport, RegistryError> {
+        let names = self.sets.get(set).ok_or_else(|| RegistryError::UnknownSet(set.to_string()))?;
+        let results = names
+            .iter()
+            .map(|name| {
+                let invariant = &self.invariants[name];
+                InvariantResult {
+                    name: name.clone(),
+                    stamp: invariant.stamp(),
+                    severity: invariant.severity(),
+                    outcome: invariant.evaluate(ctx),
+                }
+            })
+            .collect();
+        Ok(InvariantReport {
+            set: set.to_string(),
+            results,
+        })
+    }
+}
+
+fn needs_pair(name: &str) -> Outcome {
+    Outcome::Skipped(format!("{} needs a previous shard", name))
+}
+
+fn outcome_of(violations: Vec<InvariantViolation>) -> Outcome {
+    if violations.is_empty() {
+        return Outcome::Pass;
+    }
+    let reasons: Vec<String> = violations.iter().map(ToString::to_string).collect();
+    Outcome::Fail(reasons.join("; "))
+}
+
+/// Invariant 1 over the next shard's coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoCorridorNoBuild {
+    pub required_vars: Vec<String>,
+}
+
+impl NoCorridorNoBuild {
+    pub fn new(required_vars: &[&str]) -> Self {
+        Self {
+            required_vars: required_vars.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    pub fn violation(&self, next: &ResponseShard) -> Option<InvariantViolation> {
+        let required: Vec<&str> = self.required_vars.iter().map(String::as_str).collect();
+        let coords = &next.residual.coords;
+        if no_corridor_no_build(&required, coords) {
+            return None;
+        }
+        let missing = required
+            .into_iter()
+            .filter(|v| !coords.iter().any(|c| c.var_id == *v))
+            .map(str::to_string)
+            .collect();
+        Some(InvariantViolation::NoCorridor { missing })
+    }
+}
+
+impl CanonicalEncode for NoCorridorNoBuild {
+    const TYPE_TAG: &'static str = "Invariant.no_corridor_no_build";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.strs("required_vars", &self.required_vars);
+    }
+}
+
+impl Invariant for NoCorridorNoBuild {
+    fn name(&self) -> &str {
+        "no_corridor_no_build"
+    }
+
+    fn stamp(&self) -> String {
+        hexstamp(self)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Hard
+    }
+
+    fn evaluate(&self, ctx: &ShardContext<'_>) -> Outcome {
+        outcome_of(self.violation(ctx.next).into_iter().collect())
+    }
+}
+
+/// Invariant 2 via `safestep_with`; Derate and Stop both fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafeStep {
+    pub policy: SafeStepPolicy,
+}
+
+impl SafeStep {
+    /// Hard, gold and V_t breaches of the step, one violation per kind.
+    pub fn violations(&self, prev: &ResponseShard, next: &ResponseShard) -> Vec<InvariantViolation> {
+        let step = safestep_with(&prev.residual, &next.residual, &self.policy);
+        let of_kind = |kind: BreachKind| -> Vec<String> {
+            step.breaches
+                .iter()
+                .filter(|b| b.kind == kind)
+                .map(|b| b.var_id.clone())
+                .collect()
+        };
+        let mut out = Vec::new();
+        let hard = of_kind(BreachKind::Hard);
+        if !hard.is_empty() {
+            out.push(InvariantViolation::HardLimit { var_ids: hard });
+        }
+        let gold = of_kind(BreachKind::Gold);
+        if !gold.is_empty() {
+            out.push(InvariantViolation::GoldBand { var_ids: gold });
+        }
+        if !of_kind(BreachKind::Lyapunov).is_empty() {
+            out.push(InvariantViolation::VtIncreased {
+                prev: prev.residual.vt,
+                next: next.residual.vt,
+            });
+        }
+        out
+    }
+}
+
+impl CanonicalEncode for SafeStep {
+    const TYPE_TAG: &'static str = "Invariant.safestep";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.f64("safe_interior_eps", self.policy.safe_interior_eps);
+    }
+}
+
+impl Invariant for SafeStep {
+    fn name(&self) -> &str {
+        "safestep"
+    }
+
+    fn stamp(&self) -> String {
+        hexstamp(self)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Hard
+    }
+
+    fn evaluate(&self, ctx: &ShardContext<'_>) -> Outcome {
+        match ctx.prev {
+            Some(prev) => outcome_of(self.violations(prev, ctx.next)),
+            None => needs_pair(self.name()),
+        }
+    }
+}
+
+/// Invariant 3 via `KerTransition`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KerDelta {
+    pub gate: KerGate,
+}
+
+impl KerDelta {
+    pub fn violation(&self, prev: &ResponseShard, next: &ResponseShard) -> Option<InvariantViolation> {
+        let failures = KerTransition {
+            prev: prev.triad,
+            next: next.triad,
+        }
+        .evaluate(&self.gate);
+        (!failures.is_empty()).then_some(InvariantViolation::KerDelta { failures })
+    }
+}
+
+impl CanonicalEncode for KerDelta {
+    const TYPE_TAG: &'static str = "Invariant.ker_delta";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.f64("k_min", self.gate.k_min);
+        enc.f64("e_min", self.gate.e_min);
+        enc.f64("r_max", self.gate.r_max);
+    }
+}
+
+impl Invariant for KerDelta {
+    fn name(&self) -> &str {
+        "ker_delta"
+    }
+
+    fn stamp(&self) -> String {
+        hexstamp(self)
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Hard
+    }
+
+    fn evaluate(&self, ctx: &ShardContext<'_>) -> Outcome {
+        match ctx.prev {
+            Some(prev) => outcome_of(self.violation(prev, ctx.next).into_iter().collect()),
+            None => needs_pair(self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, RiskCoord, Triad};
+
+    fn shard(k: f64, coords: &[(&str, f64)]) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual::from_coords(
+                coords
+                    .iter()
+                    .map(|(id, v)| RiskCoord {
+                        var_id: id.to_string(),
+                        value: *v,
+                        safe: 0.0,
+                        gold: 0.7,
+                        hard: 1.0,
+                        weight: 0.5,
+                    })
+                    .collect(),
+            ),
+            evidence: vec![],
+            corridor_tags: vec![],
+        }
+    }
+
+    fn registry() -> InvariantRegistry {
+        InvariantRegistry::aln_core(
+            &["r_sat", "r_pfas"],
+            SafeStepPolicy::default(),
+            KerGate { k_min: 0.90, e_min: 0.85, r_max: 0.15 },
+        )
+    }
+
+    /// A domain invariant registered next to the core ones.
+    struct MarTagged;
+
+    impl Invariant for MarTagged {
+        fn name(&self) -> &str {
+            "mar_tagged"
+        }
+        fn stamp(&self) -> String {
+            "mar_tagged.v1".into()
+        }
+        fn severity(&self) -> Severity {
+            Severity::Advisory
+        }
+        fn evaluate(&self, ctx: &ShardContext<'_>) -> Outcome {
+            if ctx.next.corridor_tags.iter().any(|t| t == "mar") {
+                Outcome::Pass
+            } else {
+                Outcome::Fail("missing `mar` tag".into())
+            }
+        }
+    }
+
+    #[test]
+    fn runs_core_set_on_single_and_pair() {
+        let reg = registry();
+        let prev = shard(0.92, &[("r_sat", 0.3), ("r_pfas", 0.1)]);
+        let next = shard(0.91, &[("r_sat", 0.6), ("r_pfas", 0.1)]);
+
+        let single = reg.run_set("aln.core", &ShardContext::single(&next)).unwrap();
+        assert!(single.passes());
+        assert!(matches!(single.results[1].outcome, Outcome::Skipped(_)));
+
+        let pair = reg.run_set("aln.core", &ShardContext::pair(&prev, &next)).unwrap();
+        let blocking: Vec<&str> = pair.blocking().map(|r| r.name.as_str()).collect();
+        assert_eq!(blocking, vec!["safestep", "ker_delta"]);
+        assert!(pair.results.iter().all(|r| !r.stamp.is_empty()));
+    }
+
+    #[test]
+    fn registry_and_enforce_agree() {
+        use crate::enforcement::{enforce, EnforcementMode, InvariantContext};
+
+        let reg = registry();
+        let prev = shard(0.92, &[("r_sat", 0.3), ("r_pfas", 0.1)]);
+        let ker_only = shard(0.91, &[("r_sat", 0.2), ("r_pfas", 0.1)]);
+        let gold = shard(0.93, &[("r_sat", 0.1), ("r_pfas", 0.75)]);
+        let clean = shard(0.93, &[("r_sat", 0.2), ("r_pfas", 0.1)]);
+        for next in [&ker_only, &gold, &clean] {
+            let report = reg.run_set("aln.core", &ShardContext::pair(&prev, next)).unwrap();
+            let ctx = InvariantContext {
+                required_vars: &["r_sat", "r_pfas"],
+                prev: &prev,
+                next,
+                policy: SafeStepPolicy::default(),
+                gate: KerGate { k_min: 0.90, e_min: 0.85, r_max: 0.15 },
+            };
+            let hard = enforce(&ctx, EnforcementMode::Hard);
+            assert_eq!(report.passes(), hard.is_ok());
+            if let Err(crate::enforcement::EnforcementError::Blocked { reasons }) = hard {
+                let failed: Vec<&str> = report.blocking().map(|r| r.name.as_str()).collect();
+                let mut named: Vec<&str> = reasons.iter().map(|v| v.invariant()).collect();
+                named.dedup();
+                assert_eq!(failed, named);
+            }
+        }
+    }
+
+    #[test]
+    fn domains_register_and_group_invariants() {
+        let mut reg = registry();
+        reg.register(Box::new(MarTagged)).unwrap();
+        assert_eq!(reg.register(Box::new(MarTagged)), Err(RegistryError::Duplicate("mar_tagged".into())));
+        assert_eq!(
+            reg.define_set("mar.pilot", ["no_corridor_no_build", "missing"]),
+            Err(RegistryError::UnknownInvariant("missing".into()))
+        );
+        reg.define_set("mar.pilot", ["no_corridor_no_build", "mar_tagged"]).unwrap();
+
+        let report = reg.run_set("mar.pilot", &ShardContext::single(&shard(0.95, &[("r_sat", 0.1)]))).unwrap();
+        assert!(!report.passes());
+        assert_eq!(report.warnings().count(), 1);
+        assert!(matches!(reg.run_set("nope", &ShardContext::single(&shard(0.95, &[]))), Err(RegistryError::UnknownSet(_))));
+
+        let a = NoCorridorNoBuild::new(&["r_sat"]).stamp();
+        assert_ne!(a, NoCorridorNoBuild::new(&["r_pfas"]).stamp());
+    }
+}
@@ -0,0 +1,291 @@
+//! K/E/R derived from evidence instead of caller-supplied numbers. [file:6][file:7]
+//!
+//! - K = N_corridor-backed / N_critical over a declared critical-field list,
+//!   backed either by a residual coordinate or by a `CorridorRow` evidence ref.
+//! - E = (B − B_min) / (B_max − B_min) for a bounded benefit kernel, clipped to [0,1].
+//! - R = Σ w_j r_j over the residual coordinates, clipped to [0,1].
+//!
+//! Sums run in `var_id` order so the same inputs give bit-identical scores
+//! regardless of how the caller ordered them.
+
+use thiserror::Error;
+
+use crate::evidence::EvidenceRef;
+use crate::{RiskCoord, Triad, TriadInputs};
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum KerError {
+    #[error("no critical fields declared; K is undefined")]
+    NoCriticalFields,
+    #[error("benefit kernel `{name}` has non-finite input")]
+    NonFiniteBenefit { name: String },
+    #[error("benefit kernel `{name}` bounds are empty: B_min {b_min} >= B_max {b_max}")]
+    EmptyBenefitRange { name: String, b_min: f64, b_max: f64 },
+    #[error("coordinate `{var_id}` has non-finite value or weight")]
+    NonFiniteCoord { var_id: String },
+}
+
+/// Benefit B with its normalization bounds, e.g. kg nitrate removed per day. [file:5]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenefitKernel {
+    pub name: String,
+    pub units: String,
+    pub value: f64,
+    pub b_min: f64,
+    pub b_max: f64,
+}
+
+/// Which critical fields were backed by a corridor coordinate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnowledgeBreakdown {
+    pub backed: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+/// Where B sat inside its bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EcoBreakdown {
+    pub kernel: BenefitKernel,
+    /// True when B fell outside [B_min, B_max] and E was clipped.
+    pub clipped: bool,
+}
+
+/// Contribution w_j r_j of one coordinate to R.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskContribution {
+    pub var_id: String,
+    pub value: f64,
+    pub weight: f64,
+    pub contribution: f64,
+}
+
+/// Triad plus the inputs that produced each score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerReport {
+    pub triad: Triad,
+    pub knowledge: KnowledgeBreakdown,
+    pub eco: EcoBreakdown,
+    /// Sorted by `var_id`.
+    pub risk: Vec<RiskContribution>,
+    /// True when Σ w_j r_j exceeded 1 and R was clipped.
+    pub risk_clipped: bool,
+}
+
+impl KerReport {
+    /// Feed the derived triad into `evaluate_draft`.
+    pub fn triad_inputs(&self) -> TriadInputs {
+        TriadInputs {
+            k: self.triad.knowledge,
+            e: self.triad.eco_impact,
+            r: self.triad.risk_of_harm,
+        }
+    }
+
+    /// Contributions ordered from largest to smallest.
+    pub fn risk_drivers(&self) -> Vec<&RiskContribution> {
+        let mut drivers: Vec<&RiskContribution> = self.risk.iter().collect();
+        drivers.sort_by(|a, b| b.contribution.total_cmp(&a.contribution).then(a.var_id.cmp(&b.var_id)));
+        drivers
+    }
+}
+
+/// Derives K/E/R from a declared critical-field list. [file:6]
+#[derive(Debug, Clone, PartialEq)]
+pub struct KerCalculator {
+    critical_fields: Vec<String>,
+}
+
+impl KerCalculator {
+    /// Duplicate field names are counted once.
+    pub fn new<I, S>(critical_fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut critical_fields: Vec<String> = critical_fields.into_iter().map(Into::into).collect();
+        critical_fields.sort();
+        critical_fields.dedup();
+        Self { critical_fields }
+    }
+
+    pub fn critical_fields(&self) -> &[String] {
+        &self.critical_fields
+    }
+
+    /// K = N_corridor-backed / N_critical, where a field is backed if a coordinate carries its `var_id`.
+    pub fn knowledge(&self, coords: &[RiskCoord]) -> Result<(f64, KnowledgeBreakdown), KerError> {
+        self.knowledge_by(|f| coords.iter().any(|c| c.var_id == f))
+    }
+
+    /// K = N_corridor-backed / N_critical, where a field is backed by a `CorridorRow` reference.
+    pub fn knowledge_from_evidence(&self, evidence: &[EvidenceRef]) -> Result<(f64, KnowledgeBreakdown), KerError> {
+        self.knowledge_by(|f| evidence.iter().any(|e| e.corridor_var() == Some(f)))
+    }
+
+    fn knowledge_by(&self, is_backed: impl Fn(&str) -> bool) -> Result<(f64, KnowledgeBreakdown), KerError> {
+        if self.critical_fields.is_empty() {
+            return Err(KerError::NoCriticalFields);
+        }
+        let (backed, missing): (Vec<String>, Vec<String>) =
+            self.critical_fields.iter().cloned().partition(|f| is_backed(f));
+        let k = backed.len() as f64 / self.critical_fields.len() as f64;
+        Ok((k, KnowledgeBreakdown { backed, missing }))
+    }
+
+    /// E = (B − B_min) / (B_max − B_min), clipped to [0,1].
+    pub fn eco_impact(benefit: &BenefitKernel) -> Result<(f64, EcoBreakdown), KerError> {
+        if !(benefit.value.is_finite() && benefit.b_min.is_finite() && benefit.b_max.is_finite()) {
+            return Err(KerError::NonFiniteBenefit { name: benefit.name.clone() });
+        }
+        if benefit.b_min >= benefit.b_max {
+            return Err(KerError::EmptyBenefitRange {
+                name: benefit.name.clone(),
+                b_min: benefit.b_min,
+                b_max: benefit.b_max,
+            });
+        }
+        let raw = (benefit.value - benefit.b_min) / (benefit.b_max - benefit.b_min);
+        let e = raw.clamp(0.0, 1.0);
+        Ok((
+            e,
+            EcoBreakdown {
+                kernel: benefit.clone(),
+                clipped: e != raw,
+            },
+        ))
+    }
+
+    /// R = Σ w_j r_j, clipped to [0,1].
+    pub fn risk_of_harm(coords: &[RiskCoord]) -> Result<(f64, Vec<RiskContribution>, bool), KerError> {
+        let mut risk = Vec::with_capacity(coords.len());
+        for c in coords {
+            if !(c.value.is_finite() && c.weight.is_finite()) {
+                return Err(KerError::NonFiniteCoord { var_id: c.var_id.clone() });
+            }
+            risk.push(RiskContribution {
+                var_id: c.var_id.clone(),
+                value: c.value,
+                weight: c.weight,
+                contribution: c.weight * c.value,
+            });
+        }
+        risk.sort_by(|a, b| a.var_id.cmp(&b.var_id));
+        let raw: f64 = risk.iter().map(|c| c.contribution).sum();
+        let r = raw.clamp(0.0, 1.0);
+        Ok((r, risk, r != raw))
+    }
+
+    /// Derive the full triad and explain each score.
+    pub fn compute(&self, benefit: &BenefitKernel, coords: &[RiskCoord]) -> Result<KerReport, KerError> {
+        let knowledge = self.knowledge(coords)?;
+        Self::assemble(knowledge, benefit, coords)
+    }
+
+    /// As `compute`, but K counts critical fields backed by corridor-row evidence.
+    pub fn compute_with_evidence(
+        &self,
+        benefit: &BenefitKernel,
+        coords: &[RiskCoord],
+        evidence: &[EvidenceRef],
+    ) -> Result<KerReport, KerError> {
+        let knowledge = self.knowledge_from_evidence(evidence)?;
+        Self::assemble(knowledge, benefit, coords)
+    }
+
+    fn assemble(
+        (k, knowledge): (f64, KnowledgeBreakdown),
+        benefit: &BenefitKernel,
+        coords: &[RiskCoord],
+    ) -> Result<KerReport, KerError> {
+        let (e, eco) = Self::eco_impact(benefit)?;
+        let (r, risk, risk_clipped) = Self::risk_of_harm(coords)?;
+        Ok(KerReport {
+            triad: Triad {
+                knowledge: k,
+                eco_impact: e,
+                risk_of_harm: r,
+            },
+            knowledge,
+            eco,
+            risk,
+            risk_clipped,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(var_id: &str, value: f64, weight: f64) -> RiskCoord {
+        RiskCoord {
+            var_id: var_id.into(),
+            value,
+            safe: 0.0,
+            gold: 0.7,
+            hard: 1.0,
+            weight,
+        }
+    }
+
+    fn benefit(value: f64) -> BenefitKernel {
+        BenefitKernel {
+            name: "nitrate_removed".into(),
+            units: "kg/d".into(),
+            value,
+            b_min: 0.0,
+            b_max: 1000.0,
+        }
+    }
+
+    #[test]
+    fn derives_triad_and_reports_drivers() {
+        let calc = KerCalculator::new(["r_sat", "r_pfas", "r_temp", "r_pharma"]);
+        let coords = vec![coord("r_sat", 0.5, 0.4), coord("r_pfas", 0.25, 0.4), coord("r_temp", 0.0, 0.2)];
+        let report = calc.compute(&benefit(250.0), &coords).unwrap();
+
+        assert_eq!(report.triad.knowledge, 0.75);
+        assert_eq!(report.knowledge.missing, vec!["r_pharma".to_string()]);
+        assert_eq!(report.triad.eco_impact, 0.25);
+        assert!((report.triad.risk_of_harm - 0.3).abs() < 1e-12);
+        assert_eq!(report.risk_drivers()[0].var_id, "r_sat");
+    }
+
+    #[test]
+    fn knowledge_counts_corridor_row_evidence() {
+        let calc = KerCalculator::new(["r_sat", "r_pfas"]);
+        let evidence = vec![
+            EvidenceRef::corridor_row("mar.satcell.corridors.v1", "r_pfas"),
+            EvidenceRef::pilot("r_sat", 0.1, "m/d"),
+        ];
+        let report = calc
+            .compute_with_evidence(&benefit(0.0), &[coord("r_sat", 0.1, 1.0)], &evidence)
+            .unwrap();
+        assert_eq!(report.triad.knowledge, 0.5);
+        assert_eq!(report.knowledge.missing, vec!["r_sat".to_string()]);
+    }
+
+    #[test]
+    fn order_of_inputs_does_not_change_scores() {
+        let calc = KerCalculator::new(["r_a", "r_b", "r_c"]);
+        let mut coords = vec![coord("r_a", 0.1, 0.3), coord("r_b", 0.7, 0.3), coord("r_c", 0.2, 0.4)];
+        let a = calc.compute(&benefit(10.0), &coords).unwrap();
+        coords.reverse();
+        let b = calc.compute(&benefit(10.0), &coords).unwrap();
+        assert_eq!(a.triad.risk_of_harm.to_bits(), b.triad.risk_of_harm.to_bits());
+    }
+
+    #[test]
+    fn rejects_degenerate_inputs() {
+        assert_eq!(
+            KerCalculator::new(Vec::<String>::new()).knowledge(&[]).unwrap_err(),
+            KerError::NoCriticalFields
+        );
+        let mut b = benefit(1.0);
+        b.b_max = 0.0;
+        assert!(matches!(KerCalculator::eco_impact(&b), Err(KerError::EmptyBenefitRange { .. })));
+        let (e, eco) = KerCalculator::eco_impact(&benefit(2000.0)).unwrap();
+        assert_eq!(e, 1.0);
+        assert!(eco.clipped);
+    }
+}
@@ -1,9 +1,28 @@
 use serde::{Deserialize, Serialize};
 
 pub mod aln_invariants;
+pub mod chain;
+pub mod delta;
+pub mod enforcement;
+pub mod evidence;
+pub mod invariants;
+pub mod ker;
+pub mod ranking;
+pub mod reuse;
+pub mod sensitivity;
+pub mod session;
+pub mod signing;
+pub mod stamp;
+pub mod template;
+pub mod trace;
+pub mod validation;
+pub mod wire;
+
+use evidence::EvidenceRef;
+use validation::ValidationErrors;
 
 /// Knowledge-factor K, Eco-impact E, Risk-of-harm R. [file:6]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Triad {
     pub knowledge: f64,   // K in [0,1]
     pub eco_impact: f64,  // E in [0,1]
@@ -42,7 +61,7 @@ pub struct ResponseShard {
     pub topic: String,
     pub triad: Triad,
     pub residual: Residual,
-    pub evidence: Vec<String>,
+    pub evidence: Vec<EvidenceRef>,
     pub corridor_tags: Vec<String>,
 }
 
@@ -65,34 +84,36 @@ pub struct DraftAssessment {
     pub topic: String,
     pub base_triads: TriadInputs,
     pub base_coords: Vec<RiskCoord>,
-    pub evidence: Vec<String>,
+    pub evidence: Vec<EvidenceRef>,
     pub corridor_tags: Vec<String>,
 }
 
 /// Explicit K/E/R values for a draft. [file:6]
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TriadInputs {
     pub k: f64,
     pub e: f64,
     pub r: f64,
 }
 
-/// Wrap a draft into a ResponseShard.
-pub fn evaluate_draft(input: DraftAssessment) -> ResponseShard {
+/// Wrap a draft into a ResponseShard, rejecting malformed drafts at ingestion.
+pub fn evaluate_draft(input: DraftAssessment) -> Result<ResponseShard, ValidationErrors> {
     let residual = Residual::from_coords(input.base_coords);
     let triad = Triad {
         knowledge: input.base_triads.k,
         eco_impact: input.base_triads.e,
         risk_of_harm: input.base_triads.r,
     };
-    ResponseShard {
+    let shard = ResponseShard {
         user_did: input.user_did,
         topic: input.topic,
         triad,
         residual,
         evidence: input.evidence,
         corridor_tags: input.corridor_tags,
-    }
+    };
+    shard.validate()?;
+    Ok(shard)
 }
 
 #[cfg(test)]
@@ -0,0 +1,238 @@
+//! Governance ranking: order shards for retrieval and flag weak ones. [file:6]
+//!
+//! "Low-K or high-R shards may be down-ranked or flagged." The ranker
+//! scores every shard, attaches `LowK` / `HighR` / `NoEvidence` flags, and
+//! orders by the chosen policy. Ties keep input order. A shard that fails
+//! `validate` (e.g. a NaN K) is flagged `Invalid` and always ranks last.
+
+use std::cmp::Ordering;
+
+use crate::template::ResponseShardTemplate;
+use crate::ResponseShard;
+
+/// How shards are ordered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RankPolicy {
+    /// K descending, then E descending, then R ascending; the score breaks remaining ties.
+    Lexicographic,
+    /// Score = w_k K + w_e E − w_r R, minus penalties.
+    Weighted { w_k: f64, w_e: f64, w_r: f64 },
+}
+
+/// Subtracted from a shard's score.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Penalties {
+    /// Multiplied by V_t.
+    pub vt: f64,
+    /// Applied once when the shard carries no evidence.
+    pub no_evidence: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ShardFlag {
+    LowK,
+    HighR,
+    NoEvidence,
+    /// Fails `ResponseShard::validate`; its triad cannot be trusted.
+    Invalid,
+}
+
+#[derive(Debug, Clone)]
+pub struct RankedShard<'a> {
+    /// Position in the input slice.
+    pub index: usize,
+    pub shard: &'a ResponseShard,
+    pub score: f64,
+    pub flags: Vec<ShardFlag>,
+}
+
+impl RankedShard<'_> {
+    pub fn is_flagged(&self) -> bool {
+        !self.flags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardRanker {
+    pub policy: RankPolicy,
+    pub penalties: Penalties,
+    /// Below this K a shard is flagged `LowK`.
+    pub k_min: f64,
+    /// Above this R a shard is flagged `HighR`.
+    pub r_max: f64,
+    /// Sort every flagged shard after every unflagged one.
+    pub demote_flagged: bool,
+}
+
+impl ShardRanker {
+    /// Flag thresholds default to the recommended template's K_min and R_max.
+    pub fn new(policy: RankPolicy) -> Self {
+        let t = ResponseShardTemplate::recommended("");
+        Self {
+            policy,
+            penalties: Penalties::default(),
+            k_min: t.default_k_min,
+            r_max: t.default_r_max,
+            demote_flagged: false,
+        }
+    }
+
+    /// Take flag thresholds from a template.
+    pub fn with_template(mut self, template: &ResponseShardTemplate) -> Self {
+        self.k_min = template.default_k_min;
+        self.r_max = template.default_r_max;
+        self
+    }
+
+    pub fn with_penalties(mut self, penalties: Penalties) -> Self {
+        self.penalties = penalties;
+        self
+    }
+
+    pub fn demote_flagged(mut self, demote: bool) -> Self {
+        self.demote_flagged = demote;
+        self
+    }
+
+    pub fn flags(&self, shard: &ResponseShard) -> Vec<ShardFlag> {
+        let mut flags = Vec::new();
+        let (k, r) = (shard.triad.knowledge, shard.triad.risk_of_harm);
+        if k < self.k_min || k.is_nan() {
+            flags.push(ShardFlag::LowK);
+        }
+        if r > self.r_max || r.is_nan() {
+            flags.push(ShardFlag::HighR);
+        }
+        if shard.evidence.is_empty() {
+            flags.push(ShardFlag::NoEvidence);
+        }
+        if shard.validate().is_err() {
+            flags.push(ShardFlag::Invalid);
+        }
+        flags
+    }
+
+    /// Higher is better. `Lexicographic` scores as K + E − R; an invalid shard scores −∞.
+    pub fn score(&self, shard: &ResponseShard) -> f64 {
+        if shard.validate().is_err() {
+            return f64::NEG_INFINITY;
+        }
+        let t = &shard.triad;
+        let base = match self.policy {
+            RankPolicy::Lexicographic => t.knowledge + t.eco_impact - t.risk_of_harm,
+            RankPolicy::Weighted { w_k, w_e, w_r } => w_k * t.knowledge + w_e * t.eco_impact - w_r * t.risk_of_harm,
+        };
+        let mut score = base - self.penalties.vt * shard.residual.vt;
+        if shard.evidence.is_empty() {
+            score -= self.penalties.no_evidence;
+        }
+        score
+    }
+
+    fn compare(&self, a: &RankedShard<'_>, b: &RankedShard<'_>) -> Ordering {
+        let invalid = |r: &RankedShard<'_>| r.flags.contains(&ShardFlag::Invalid);
+        let demoted = if self.demote_flagged {
+            a.is_flagged().cmp(&b.is_flagged())
+        } else {
+            Ordering::Equal
+        };
+        let (ta, tb) = (&a.shard.triad, &b.shard.triad);
+        let policy = match self.policy {
+            RankPolicy::Lexicographic => tb
+                .knowledge
+                .total_cmp(&ta.knowledge)
+                .then(tb.eco_impact.total_cmp(&ta.eco_impact))
+                .then(ta.risk_of_harm.total_cmp(&tb.risk_of_harm)),
+            RankPolicy::Weighted { .. } => Ordering::Equal,
+        };
+        invalid(a)
+            .cmp(&invalid(b))
+            .then(demoted)
+            .then(policy)
+            .then(b.score.total_cmp(&a.score))
+    }
+
+    /// Best first.
+    pub fn rank<'a>(&self, shards: &'a [ResponseShard]) -> Vec<RankedShard<'a>> {
+        let mut ranked: Vec<RankedShard<'a>> = shards
+            .iter()
+            .enumerate()
+            .map(|(index, shard)| RankedShard {
+                index,
+                shard,
+                score: self.score(shard),
+                flags: self.flags(shard),
+            })
+            .collect();
+        ranked.sort_by(|a, b| self.compare(a, b));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::EvidenceRef;
+    use crate::{Residual, Triad};
+
+    fn shard(k: f64, e: f64, r: f64, vt: f64, evidence: bool) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: e, risk_of_harm: r },
+            residual: Residual { vt, coords: vec![] },
+            evidence: if evidence { vec![EvidenceRef::equation("K=N_backed/N_critical")] } else { vec![] },
+            corridor_tags: vec![],
+        }
+    }
+
+    #[test]
+    fn lexicographic_orders_and_flags() {
+        let shards = vec![
+            shard(0.95, 0.80, 0.10, 0.2, true),
+            shard(0.95, 0.90, 0.20, 0.2, true),
+            shard(0.80, 0.99, 0.05, 0.1, false),
+        ];
+        let ranked = ShardRanker::new(RankPolicy::Lexicographic).rank(&shards);
+        let order: Vec<usize> = ranked.iter().map(|r| r.index).collect();
+        assert_eq!(order, vec![1, 0, 2]);
+        assert_eq!(ranked[0].flags, vec![ShardFlag::HighR]);
+        assert_eq!(ranked[2].flags, vec![ShardFlag::LowK, ShardFlag::NoEvidence]);
+
+        let demoted = ShardRanker::new(RankPolicy::Lexicographic).demote_flagged(true).rank(&shards);
+        assert_eq!(demoted[0].index, 0);
+    }
+
+    #[test]
+    fn weighted_score_applies_penalties() {
+        let shards = vec![shard(0.95, 0.90, 0.10, 0.50, true), shard(0.94, 0.90, 0.10, 0.10, false)];
+        let policy = RankPolicy::Weighted { w_k: 1.0, w_e: 1.0, w_r: 1.0 };
+
+        assert_eq!(ShardRanker::new(policy).rank(&shards)[0].index, 0);
+
+        let vt_only = ShardRanker::new(policy).with_penalties(Penalties { vt: 1.0, no_evidence: 0.0 });
+        assert_eq!(vt_only.rank(&shards)[0].index, 1);
+
+        let both = ShardRanker::new(policy).with_penalties(Penalties { vt: 1.0, no_evidence: 0.5 });
+        let ranked = both.rank(&shards);
+        assert_eq!(ranked[0].index, 0);
+        assert!((ranked[0].score - 1.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn non_finite_triad_ranks_last_and_is_flagged() {
+        let shards = vec![
+            shard(0.80, 0.90, 0.10, 0.2, true),
+            shard(f64::NAN, 0.90, 0.10, 0.2, true),
+            shard(0.95, 0.90, f64::NAN, 0.2, true),
+        ];
+        for policy in [RankPolicy::Lexicographic, RankPolicy::Weighted { w_k: 1.0, w_e: 1.0, w_r: 1.0 }] {
+            let ranked = ShardRanker::new(policy).rank(&shards);
+            assert_eq!(ranked[0].index, 0);
+            assert!(ranked[1..].iter().all(|r| r.flags.contains(&ShardFlag::Invalid)));
+            assert_eq!(ranked[1].score, f64::NEG_INFINITY);
+        }
+        let flags = ShardRanker::new(RankPolicy::Lexicographic).flags(&shards[1]);
+        assert_eq!(flags, vec![ShardFlag::LowK, ShardFlag::Invalid]);
+    }
+}
@@ -0,0 +1,261 @@
+//! "No shard, no reuse": gate downstream reuse of text or metrics. [file:6]
+//!
+//! A tool asking to reuse an artifact must name it; the gate looks up the
+//! backing shard, checks that its hexstamp matches its content, that it
+//! validates, and that it clears the K/E/R thresholds. Every decision is
+//! appended to an audit log.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::aln_invariants::{KerAxis, KerGate};
+use crate::stamp::{hexstamp, verify_hexstamp, StampError};
+use crate::template::ResponseShardTemplate;
+use crate::validation::ValidationErrors;
+use crate::ResponseShard;
+
+/// What a downstream tool wants to reuse, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReuseRequest {
+    pub artifact_id: String,
+    pub tool: String,
+    pub intended_use: String,
+}
+
+/// A shard as persisted, with the hexstamp it was published under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredShard {
+    pub shard: ResponseShard,
+    pub hexstamp: String,
+}
+
+impl StoredShard {
+    /// Stamp `shard` with its current content.
+    pub fn stamped(shard: ResponseShard) -> Self {
+        let hexstamp = hexstamp(&shard);
+        Self { shard, hexstamp }
+    }
+}
+
+/// Where backing shards live, keyed by artifact id.
+pub trait ShardStore {
+    fn get(&self, artifact_id: &str) -> Option<StoredShard>;
+}
+
+/// In-memory store for tests and single-process pipelines.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryShardStore {
+    shards: BTreeMap<String, StoredShard>,
+}
+
+impl MemoryShardStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, artifact_id: impl Into<String>, stored: StoredShard) {
+        self.shards.insert(artifact_id.into(), stored);
+    }
+}
+
+impl ShardStore for MemoryShardStore {
+    fn get(&self, artifact_id: &str) -> Option<StoredShard> {
+        self.shards.get(artifact_id).cloned()
+    }
+}
+
+/// Why reuse was denied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DenyReason {
+    NoShard,
+    Stamp(StampError),
+    Invalid(ValidationErrors),
+    BelowKMin { k: f64, min: f64 },
+    BelowEMin { e: f64, min: f64 },
+    AboveRMax { r: f64, max: f64 },
+}
+
+impl fmt::Display for DenyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DenyReason::NoShard => write!(f, "no backing shard"),
+            DenyReason::Stamp(e) => write!(f, "{}", e),
+            DenyReason::Invalid(e) => write!(f, "{}", e),
+            DenyReason::BelowKMin { k, min } => write!(f, "K {} is below K_min {}", k, min),
+            DenyReason::BelowEMin { e, min } => write!(f, "E {} is below E_min {}", e, min),
+            DenyReason::AboveRMax { r, max } => write!(f, "R {} is above R_max {}", r, max),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReuseDecision {
+    Grant { hexstamp: String },
+    Deny { reasons: Vec<DenyReason> },
+}
+
+impl ReuseDecision {
+    pub fn is_granted(&self) -> bool {
+        matches!(self, ReuseDecision::Grant { .. })
+    }
+}
+
+/// One audited decision: what was asked for, under which shard, and the outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReuseLogEntry {
+    pub seq: u64,
+    pub request: ReuseRequest,
+    /// Stamp of the backing shard, when one was found.
+    pub hexstamp: Option<String>,
+    pub granted: bool,
+    pub reasons: Vec<String>,
+}
+
+pub struct ReuseGate<S: ShardStore> {
+    store: S,
+    pub gate: KerGate,
+    log: Vec<ReuseLogEntry>,
+}
+
+impl<S: ShardStore> ReuseGate<S> {
+    /// Thresholds come from the template's `default_K_min`, `default_E_min`, `default_R_max`.
+    pub fn new(store: S, template: &ResponseShardTemplate) -> Self {
+        Self {
+            store,
+            gate: KerGate::from_template(template),
+            log: Vec::new(),
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    fn check(&self, stored: &StoredShard) -> Vec<DenyReason> {
+        let mut reasons = Vec::new();
+        if let Err(e) = verify_hexstamp(&stored.shard, &stored.hexstamp) {
+            reasons.push(DenyReason::Stamp(e));
+        }
+        if let Err(e) = stored.shard.validate() {
+            reasons.push(DenyReason::Invalid(e));
+        }
+        let t = &stored.shard.triad;
+        for axis in self.gate.misses(t) {
+            reasons.push(match axis {
+                KerAxis::K => DenyReason::BelowKMin { k: t.knowledge, min: self.gate.k_min },
+                KerAxis::E => DenyReason::BelowEMin { e: t.eco_impact, min: self.gate.e_min },
+                KerAxis::R => DenyReason::AboveRMax { r: t.risk_of_harm, max: self.gate.r_max },
+            });
+        }
+        reasons
+    }
+
+    /// Decide on `request` and log the decision.
+    pub fn request(&mut self, request: ReuseRequest) -> ReuseDecision {
+        let stored = self.store.get(&request.artifact_id);
+        let decision = match &stored {
+            None => ReuseDecision::Deny { reasons: vec![DenyReason::NoShard] },
+            Some(stored) => {
+                let reasons = self.check(stored);
+                if reasons.is_empty() {
+                    ReuseDecision::Grant { hexstamp: stored.hexstamp.clone() }
+                } else {
+                    ReuseDecision::Deny { reasons }
+                }
+            }
+        };
+
+        let reasons = match &decision {
+            ReuseDecision::Grant { .. } => Vec::new(),
+            ReuseDecision::Deny { reasons } => reasons.iter().map(ToString::to_string).collect(),
+        };
+        self.log.push(ReuseLogEntry {
+            seq: self.log.len() as u64,
+            request,
+            hexstamp: stored.map(|s| s.hexstamp),
+            granted: decision.is_granted(),
+            reasons,
+        });
+        decision
+    }
+
+    pub fn log(&self) -> &[ReuseLogEntry] {
+        &self.log
+    }
+
+    pub fn grants(&self) -> impl Iterator<Item = &ReuseLogEntry> {
+        self.log.iter().filter(|e| e.granted)
+    }
+
+    pub fn export_log_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::EvidenceRef;
+    use crate::{Residual, Triad};
+
+    fn shard(k: f64) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual { vt: 0.12, coords: vec![] },
+            evidence: vec![EvidenceRef::equation("K=N_backed/N_critical")],
+            corridor_tags: vec!["mar".into()],
+        }
+    }
+
+    fn request(artifact_id: &str) -> ReuseRequest {
+        ReuseRequest {
+            artifact_id: artifact_id.into(),
+            tool: "retrieval".into(),
+            intended_use: "quote".into(),
+        }
+    }
+
+    fn gate() -> ReuseGate<MemoryShardStore> {
+        let mut store = MemoryShardStore::new();
+        store.insert("answer-1", StoredShard::stamped(shard(0.95)));
+        store.insert("answer-2", StoredShard::stamped(shard(0.80)));
+        let mut tampered = StoredShard::stamped(shard(0.95));
+        tampered.shard.triad.risk_of_harm = 0.01;
+        store.insert("answer-3", tampered);
+        ReuseGate::new(store, &ResponseShardTemplate::recommended("bostrom18..."))
+    }
+
+    #[test]
+    fn grants_and_denials_are_logged() {
+        let mut gate = gate();
+        assert!(gate.request(request("answer-1")).is_granted());
+        assert_eq!(
+            gate.request(request("missing")),
+            ReuseDecision::Deny { reasons: vec![DenyReason::NoShard] }
+        );
+        let ReuseDecision::Deny { reasons } = gate.request(request("answer-2")) else {
+            panic!("low-K shard was granted");
+        };
+        assert!(matches!(reasons[..], [DenyReason::BelowKMin { .. }]));
+
+        assert_eq!(gate.log().len(), 3);
+        let grants: Vec<&ReuseLogEntry> = gate.grants().collect();
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].request.artifact_id, "answer-1");
+        assert_eq!(gate.log()[1].hexstamp, None);
+        assert!(gate.export_log_json().unwrap().contains("\"intended_use\": \"quote\""));
+    }
+
+    #[test]
+    fn tampered_shard_is_denied() {
+        let mut gate = gate();
+        let ReuseDecision::Deny { reasons } = gate.request(request("answer-3")) else {
+            panic!("tampered shard was granted");
+        };
+        assert!(matches!(reasons[..], [DenyReason::Stamp(StampError::Mismatch { .. })]));
+    }
+}
@@ -0,0 +1,340 @@
+//! Local sensitivity and tornado analysis of V_t and each r_x. [file:6][file:7]
+//!
+//! Any kernel that maps physical inputs to a `Residual` can be analysed.
+//! Partials are finite differences at the nominal point, central where the
+//! declared range allows and one-sided at its edges. A zero partial is
+//! checked against a scan of the range: if the output moves elsewhere, the
+//! input sits on a clamp of the normalization rather than being irrelevant.
+//! The tornado table swings each input across its range, others nominal.
+
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+
+use thiserror::Error;
+
+use crate::Residual;
+
+/// Step as a fraction of the declared range.
+pub const DEFAULT_STEP_FRACTION: f64 = 1e-3;
+/// Points scanned across a range to tell a clamp from an inert input.
+pub const DEFAULT_SCAN_POINTS: usize = 16;
+
+#[derive(Debug, Error)]
+pub enum SensitivityError {
+    #[error("kernel declares no inputs")]
+    NoInputs,
+    #[error("input `{name}` range [{low}, {high}] is empty or non-finite, or misses nominal {nominal}")]
+    InvalidRange { name: String, low: f64, high: f64, nominal: f64 },
+    #[error("kernel failed at {name} = {value}: {source}")]
+    Kernel {
+        name: String,
+        value: f64,
+        #[source]
+        source: Box<dyn StdError + Send + Sync>,
+    },
+}
+
+/// A physical input with its nominal value and declared range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputSpec {
+    pub name: String,
+    pub units: String,
+    pub nominal: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl InputSpec {
+    pub fn new(name: impl Into<String>, units: impl Into<String>, nominal: f64, low: f64, high: f64) -> Self {
+        Self {
+            name: name.into(),
+            units: units.into(),
+            nominal,
+            low,
+            high,
+        }
+    }
+}
+
+/// A kernel from physical inputs, in `inputs()` order, to a residual.
+pub trait ResidualKernel {
+    type Error: StdError + Send + Sync + 'static;
+
+    fn inputs(&self) -> Vec<InputSpec>;
+
+    fn residual(&self, x: &[f64]) -> Result<Residual, Self::Error>;
+}
+
+/// ∂V_t and ∂r_x with respect to one input, per unit of that input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partial {
+    pub input: String,
+    pub units: String,
+    pub d_vt: f64,
+    /// Keyed by `var_id`.
+    pub d_coords: BTreeMap<String, f64>,
+}
+
+/// An input whose partial is zero at nominal although the output moves elsewhere in range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClampNotice {
+    pub input: String,
+    pub nominal: f64,
+    /// Coordinates clamped at nominal; `V_t` when only the residual was checked.
+    pub coords: Vec<String>,
+}
+
+/// One bar of the tornado: V_t at the ends of the input's range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TornadoRow {
+    pub input: String,
+    pub units: String,
+    pub low: f64,
+    pub high: f64,
+    pub vt_low: f64,
+    pub vt_high: f64,
+    /// |vt_high - vt_low|.
+    pub swing: f64,
+    pub on_clamp: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensitivityReport {
+    pub vt_nominal: f64,
+    pub partials: Vec<Partial>,
+    pub notices: Vec<ClampNotice>,
+    /// Largest swing first.
+    pub tornado: Vec<TornadoRow>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensitivityAnalysis {
+    pub step_fraction: f64,
+    pub scan_points: usize,
+}
+
+impl Default for SensitivityAnalysis {
+    fn default() -> Self {
+        Self {
+            step_fraction: DEFAULT_STEP_FRACTION,
+            scan_points: DEFAULT_SCAN_POINTS,
+        }
+    }
+}
+
+fn coord_values(residual: &Residual) -> BTreeMap<String, f64> {
+    residual.coords.iter().map(|c| (c.var_id.clone(), c.value)).collect()
+}
+
+impl SensitivityAnalysis {
+    fn specs<K: ResidualKernel>(kernel: &K) -> Result<Vec<InputSpec>, SensitivityError> {
+        let specs = kernel.inputs();
+        if specs.is_empty() {
+            return Err(SensitivityError::NoInputs);
+        }
+        for s in &specs {
+            let finite = [s.low, s.high, s.nominal].iter().all(|v| v.is_finite());
+            if !finite || s.low > s.high || !(s.low..=s.high).contains(&s.nominal) {
+                return Err(SensitivityError::InvalidRange {
+                    name: s.name.clone(),
+                    low: s.low,
+                    high: s.high,
+                    nominal: s.nominal,
+                });
+            }
+        }
+        Ok(specs)
+    }
+
+    /// Residual with input `i` set to `value`, the rest nominal.
+    fn eval_at<K: ResidualKernel>(
+        kernel: &K,
+        specs: &[InputSpec],
+        i: usize,
+        value: f64,
+    ) -> Result<Residual, SensitivityError> {
+        let mut x: Vec<f64> = specs.iter().map(|s| s.nominal).collect();
+        x[i] = value;
+        kernel.residual(&x).map_err(|e| SensitivityError::Kernel {
+            name: specs[i].name.clone(),
+            value,
+            source: Box::new(e),
+        })
+    }
+
+    /// Coordinates (or `V_t`) that move somewhere across the range of input `i`.
+    fn moving<K: ResidualKernel>(
+        &self,
+        kernel: &K,
+        specs: &[InputSpec],
+        i: usize,
+    ) -> Result<Vec<String>, SensitivityError> {
+        let s = &specs[i];
+        let n = self.scan_points.max(2);
+        let base = Self::eval_at(kernel, specs, i, s.low)?;
+        let (base_vt, base_coords) = (base.vt, coord_values(&base));
+        let mut moved: Vec<String> = Vec::new();
+        for k in 1..n {
+            let r = Self::eval_at(kernel, specs, i, s.low + (s.high - s.low) * k as f64 / (n - 1) as f64)?;
+            for (var_id, v) in coord_values(&r) {
+                if base_coords.get(&var_id) != Some(&v) && !moved.contains(&var_id) {
+                    moved.push(var_id);
+                }
+            }
+            if r.vt != base_vt && moved.is_empty() {
+                moved.push("V_t".into());
+            }
+        }
+        Ok(moved)
+    }
+
+    pub fn analyze<K: ResidualKernel>(&self, kernel: &K) -> Result<SensitivityReport, SensitivityError> {
+        let specs = Self::specs(kernel)?;
+        let nominal_x: Vec<f64> = specs.iter().map(|s| s.nominal).collect();
+        let nominal = kernel.residual(&nominal_x).map_err(|e| SensitivityError::Kernel {
+            name: specs[0].name.clone(),
+            value: specs[0].nominal,
+            source: Box::new(e),
+        })?;
+
+        let mut partials = Vec::with_capacity(specs.len());
+        let mut notices = Vec::new();
+        let mut tornado = Vec::with_capacity(specs.len());
+        for (i, s) in specs.iter().enumerate() {
+            let h = self.step_fraction * (s.high - s.low);
+            let (lo, hi) = ((s.nominal - h).max(s.low), (s.nominal + h).min(s.high));
+            let mut partial = Partial {
+                input: s.name.clone(),
+                units: s.units.clone(),
+                d_vt: 0.0,
+                d_coords: coord_values(&nominal).into_keys().map(|k| (k, 0.0)).collect(),
+            };
+            if hi > lo {
+                let (r_lo, r_hi) = (Self::eval_at(kernel, &specs, i, lo)?, Self::eval_at(kernel, &specs, i, hi)?);
+                partial.d_vt = (r_hi.vt - r_lo.vt) / (hi - lo);
+                let c_lo = coord_values(&r_lo);
+                for (var_id, v_hi) in coord_values(&r_hi) {
+                    let v_lo = c_lo.get(&var_id).copied().unwrap_or(v_hi);
+                    partial.d_coords.insert(var_id, (v_hi - v_lo) / (hi - lo));
+                }
+            }
+
+            let flat: Vec<&String> = partial.d_coords.iter().filter(|(_, d)| **d == 0.0).map(|(k, _)| k).collect();
+            let mut clamped = Vec::new();
+            if s.high > s.low && (partial.d_vt == 0.0 || !flat.is_empty()) {
+                for var_id in self.moving(kernel, &specs, i)? {
+                    let flat_here = flat.contains(&&var_id) || (var_id == "V_t" && partial.d_vt == 0.0);
+                    if flat_here {
+                        clamped.push(var_id);
+                    }
+                }
+            }
+            if !clamped.is_empty() {
+                notices.push(ClampNotice {
+                    input: s.name.clone(),
+                    nominal: s.nominal,
+                    coords: clamped,
+                });
+            }
+
+            let (vt_low, vt_high) = (
+                Self::eval_at(kernel, &specs, i, s.low)?.vt,
+                Self::eval_at(kernel, &specs, i, s.high)?.vt,
+            );
+            tornado.push(TornadoRow {
+                input: s.name.clone(),
+                units: s.units.clone(),
+                low: s.low,
+                high: s.high,
+                vt_low,
+                vt_high,
+                swing: (vt_high - vt_low).abs(),
+                on_clamp: notices.last().is_some_and(|n| n.input == s.name),
+            });
+            partials.push(partial);
+        }
+        tornado.sort_by(|a, b| b.swing.total_cmp(&a.swing));
+
+        Ok(SensitivityReport {
+            vt_nominal: nominal.vt,
+            partials,
+            notices,
+            tornado,
+        })
+    }
+}
+
+impl SensitivityReport {
+    pub fn partial(&self, input: &str) -> Option<&Partial> {
+        self.partials.iter().find(|p| p.input == input)
+    }
+
+    pub fn is_clamped(&self, input: &str) -> bool {
+        self.notices.iter().any(|n| n.input == input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RiskCoord;
+
+    /// r_a = clamp((a - 1) / 4), r_b = clamp(b / 10), weights 0.5/0.4; `c` is inert.
+    struct Toy {
+        nominal: [f64; 3],
+    }
+
+    impl ResidualKernel for Toy {
+        type Error = std::convert::Infallible;
+
+        fn inputs(&self) -> Vec<InputSpec> {
+            vec![
+                InputSpec::new("a", "m", self.nominal[0], 0.0, 5.0),
+                InputSpec::new("b", "ng/L", self.nominal[1], 0.0, 20.0),
+                InputSpec::new("c", "-", self.nominal[2], 0.0, 1.0),
+            ]
+        }
+
+        fn residual(&self, x: &[f64]) -> Result<Residual, Self::Error> {
+            let coord = |var_id: &str, value: f64, weight| RiskCoord {
+                var_id: var_id.into(),
+                value: value.clamp(0.0, 1.0),
+                safe: 0.0,
+                gold: 0.5,
+                hard: 1.0,
+                weight,
+            };
+            Ok(Residual::from_coords(vec![
+                coord("r_a", (x[0] - 1.0) / 4.0, 0.5),
+                coord("r_b", x[1] / 10.0, 0.4),
+            ]))
+        }
+    }
+
+    #[test]
+    fn partials_match_the_linear_segment() {
+        let report = SensitivityAnalysis::default().analyze(&Toy { nominal: [3.0, 4.0, 0.5] }).unwrap();
+        let a = report.partial("a").unwrap();
+        assert!((a.d_coords["r_a"] - 0.25).abs() < 1e-9);
+        assert!((a.d_vt - 0.125).abs() < 1e-9);
+        assert_eq!(a.d_coords["r_b"], 0.0);
+        assert!((report.partial("b").unwrap().d_vt - 0.04).abs() < 1e-9);
+        assert!(report.notices.is_empty());
+
+        // Both cross their whole clamp; the swing is the weight.
+        let order: Vec<&str> = report.tornado.iter().map(|r| r.input.as_str()).collect();
+        assert_eq!(order, ["a", "b", "c"]);
+        assert_eq!(report.tornado[2].swing, 0.0);
+    }
+
+    #[test]
+    fn zero_gradient_on_a_clamp_is_flagged() {
+        let report = SensitivityAnalysis::default().analyze(&Toy { nominal: [0.5, 15.0, 0.5] }).unwrap();
+        assert_eq!(report.partial("a").unwrap().d_vt, 0.0);
+        assert_eq!(report.partial("b").unwrap().d_vt, 0.0);
+        assert!(report.is_clamped("a"));
+        assert_eq!(report.notices[1].coords, ["r_b"]);
+        assert!(!report.is_clamped("c"));
+        assert!(report.tornado.iter().all(|r| r.on_clamp == (r.input != "c")));
+    }
+}
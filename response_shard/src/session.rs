@@ -0,0 +1,207 @@
+//! Session-level roll-up over the ordered shards of one research session. [file:6]
+//!
+//! K and E are weight-averaged across turns (weight 1 unless the caller says
+//! otherwise), R is reported as both mean and worst case, and V_t is kept as a
+//! trajectory so any rise between consecutive turns is visible. A turn
+//! regresses when any axis of its delta against the previous turn worsened.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::delta::{DeltaEpsilons, ShardDelta};
+use crate::ResponseShard;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SessionError {
+    #[error("session has no turns")]
+    Empty,
+    #[error("turn {turn} has invalid weight {weight}; weights must be finite and >= 0")]
+    InvalidWeight { turn: usize, weight: f64 },
+    #[error("turn weights sum to zero")]
+    ZeroTotalWeight,
+}
+
+/// A step where V_t rose from turn `turn - 1` to `turn`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VtRise {
+    pub turn: usize,
+    pub prev: f64,
+    pub next: f64,
+}
+
+/// A turn that worsened at least one axis relative to its predecessor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub turn: usize,
+    pub delta: ShardDelta,
+}
+
+/// Roll-up used to decide which sessions get promoted to corridor work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub turns: usize,
+    /// Weighted mean K.
+    pub knowledge: f64,
+    /// Weighted mean E.
+    pub eco_impact: f64,
+    pub risk_mean: f64,
+    pub risk_worst: f64,
+    /// First turn at which `risk_worst` occurred.
+    pub risk_worst_turn: usize,
+    /// V_t per turn, in session order.
+    pub vt: Vec<f64>,
+    pub vt_rises: Vec<VtRise>,
+    /// Number of turns carrying each corridor tag.
+    pub tag_coverage: BTreeMap<String, usize>,
+    pub regressed: Vec<Regression>,
+}
+
+impl SessionSummary {
+    /// Equal weights, zero tolerances.
+    pub fn from_shards(shards: &[ResponseShard]) -> Result<Self, SessionError> {
+        Self::from_weighted(shards.iter().map(|s| (s, 1.0)), &DeltaEpsilons::default())
+    }
+
+    /// Per-turn weights for K/E; moves within `eps` neither flag V_t nor count as regressions.
+    pub fn from_weighted<'a, I>(turns: I, eps: &DeltaEpsilons) -> Result<Self, SessionError>
+    where
+        I: IntoIterator<Item = (&'a ResponseShard, f64)>,
+    {
+        let turns: Vec<(&ResponseShard, f64)> = turns.into_iter().collect();
+        if turns.is_empty() {
+            return Err(SessionError::Empty);
+        }
+        for (turn, &(_, weight)) in turns.iter().enumerate() {
+            if !weight.is_finite() || weight < 0.0 {
+                return Err(SessionError::InvalidWeight { turn, weight });
+            }
+        }
+        let total: f64 = turns.iter().map(|(_, w)| w).sum();
+        if total == 0.0 {
+            return Err(SessionError::ZeroTotalWeight);
+        }
+
+        let knowledge = turns.iter().map(|(s, w)| w * s.triad.knowledge).sum::<f64>() / total;
+        let eco_impact = turns.iter().map(|(s, w)| w * s.triad.eco_impact).sum::<f64>() / total;
+        let risk_mean = turns.iter().map(|(s, _)| s.triad.risk_of_harm).sum::<f64>() / turns.len() as f64;
+        let (risk_worst_turn, risk_worst) = turns
+            .iter()
+            .map(|(s, _)| s.triad.risk_of_harm)
+            .enumerate()
+            .fold((0, f64::NEG_INFINITY), |best, (i, r)| if r > best.1 { (i, r) } else { best });
+
+        let vt: Vec<f64> = turns.iter().map(|(s, _)| s.residual.vt).collect();
+        let vt_rises = vt
+            .windows(2)
+            .enumerate()
+            .filter(|(_, w)| w[1] - w[0] > eps.vt)
+            .map(|(i, w)| VtRise { turn: i + 1, prev: w[0], next: w[1] })
+            .collect();
+
+        let mut tag_coverage = BTreeMap::new();
+        for (shard, _) in &turns {
+            let mut tags: Vec<&String> = shard.corridor_tags.iter().collect();
+            tags.sort();
+            tags.dedup();
+            for tag in tags {
+                *tag_coverage.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let regressed = turns
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, w)| {
+                let delta = w[1].0.delta_with(w[0].0, eps);
+                let worse = delta.worsened().next().is_some();
+                worse.then_some(Regression { turn: i + 1, delta })
+            })
+            .collect();
+
+        Ok(Self {
+            turns: turns.len(),
+            knowledge,
+            eco_impact,
+            risk_mean,
+            risk_worst,
+            risk_worst_turn,
+            vt,
+            vt_rises,
+            tag_coverage,
+            regressed,
+        })
+    }
+
+    /// True if V_t never rose between consecutive turns.
+    pub fn vt_monotone(&self) -> bool {
+        self.vt_rises.is_empty()
+    }
+
+    /// Share of turns carrying `tag`, in [0,1].
+    pub fn tag_fraction(&self, tag: &str) -> f64 {
+        self.tag_coverage.get(tag).copied().unwrap_or(0) as f64 / self.turns as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::delta::Axis;
+    use crate::{Residual, Triad};
+
+    fn shard(k: f64, r: f64, vt: f64, tags: &[&str]) -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: r },
+            residual: Residual { vt, coords: vec![] },
+            evidence: vec![],
+            corridor_tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn rolls_up_a_session() {
+        let shards = vec![
+            shard(0.80, 0.20, 0.30, &["mar"]),
+            shard(0.90, 0.10, 0.25, &["mar", "sat"]),
+            shard(0.85, 0.30, 0.28, &["mar"]),
+        ];
+        let s = SessionSummary::from_shards(&shards).unwrap();
+
+        assert_eq!(s.turns, 3);
+        assert!((s.knowledge - 0.85).abs() < 1e-12);
+        assert!((s.risk_mean - 0.20).abs() < 1e-12);
+        assert_eq!((s.risk_worst_turn, s.risk_worst), (2, 0.30));
+        assert_eq!(s.vt_rises, vec![VtRise { turn: 2, prev: 0.25, next: 0.28 }]);
+        assert!(!s.vt_monotone());
+        assert_eq!(s.tag_fraction("mar"), 1.0);
+        assert_eq!(s.tag_coverage["sat"], 1);
+
+        assert_eq!(s.regressed.len(), 1);
+        assert_eq!(s.regressed[0].turn, 2);
+        assert!(s.regressed[0].delta.worsened().any(|a| a.axis == Axis::R));
+    }
+
+    #[test]
+    fn weights_and_epsilons() {
+        let a = shard(0.80, 0.20, 0.30, &[]);
+        let b = shard(0.90, 0.20, 0.3005, &[]);
+        let eps = DeltaEpsilons { vt: 1e-3, ..DeltaEpsilons::default() };
+        let s = SessionSummary::from_weighted([(&a, 1.0), (&b, 3.0)], &eps).unwrap();
+        assert!((s.knowledge - 0.875).abs() < 1e-12);
+        assert!(s.vt_monotone());
+        assert!(s.regressed.is_empty());
+
+        assert_eq!(SessionSummary::from_shards(&[]), Err(SessionError::Empty));
+        assert_eq!(
+            SessionSummary::from_weighted([(&a, 0.0)], &eps),
+            Err(SessionError::ZeroTotalWeight)
+        );
+        assert!(matches!(
+            SessionSummary::from_weighted([(&a, f64::NAN)], &eps),
+            Err(SessionError::InvalidWeight { turn: 0, .. })
+        ));
+    }
+}
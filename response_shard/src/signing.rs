@@ -0,0 +1,285 @@
+//! DID signing and verification over the canonical shard encoding. [file:3][file:11]
+//!
+//! Signatures are secp256k1 ECDSA over SHA-256 of `canonical_bytes()`, the
+//! same scheme bostrom accounts use, so a signature is bound to its
+//! `signer_addr` by recomputing `bostrom1…` from the embedded public key.
+//! Gates should verify a `ShardSignature` rather than trust a
+//! `didsignature_valid` flag set elsewhere.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use bech32::{ToBase32, Variant};
+use k256::ecdsa::signature::{Signer, Verifier};
+use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::stamp::{from_hex, to_hex, CanonicalEncode};
+
+/// Human-readable prefix of bostrom account addresses.
+pub const BOSTROM_HRP: &str = "bostrom";
+
+#[derive(Debug, Error)]
+pub enum SigningError {
+    #[error("keystore I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("keystore is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid key material: {0}")]
+    InvalidKey(String),
+    #[error("key derives {derived}, but is bound to {expected}")]
+    AddressMismatch { expected: String, derived: String },
+    #[error("signer {0} is not trusted by this verifier")]
+    UntrustedSigner(String),
+    #[error("signature does not verify for {0}")]
+    BadSignature(String),
+}
+
+/// Detached signature carried next to a shard. [file:11]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardSignature {
+    pub signer_addr: String,
+    /// SEC1-compressed secp256k1 public key, `0x`-hex.
+    pub public_key_hex: String,
+    /// 64-byte r‖s ECDSA signature, `0x`-hex.
+    pub signature_hex: String,
+}
+
+pub trait ShardSigner {
+    fn signer_addr(&self) -> &str;
+
+    /// Sign already-canonical bytes; use `sign_shard` for shard structs.
+    fn sign(&self, canonical: &[u8]) -> Result<ShardSignature, SigningError>;
+}
+
+pub trait ShardVerifier {
+    /// Verify a signature over already-canonical bytes.
+    fn verify(&self, canonical: &[u8], signature: &ShardSignature) -> Result<(), SigningError>;
+}
+
+pub fn sign_shard<T: CanonicalEncode + ?Sized>(
+    signer: &dyn ShardSigner,
+    value: &T,
+) -> Result<ShardSignature, SigningError> {
+    signer.sign(&value.canonical_bytes())
+}
+
+pub fn verify_shard<T: CanonicalEncode + ?Sized>(
+    verifier: &dyn ShardVerifier,
+    value: &T,
+    signature: &ShardSignature,
+) -> Result<(), SigningError> {
+    verifier.verify(&value.canonical_bytes(), signature)
+}
+
+/// `bostrom1…` address of a compressed public key: bech32(ripemd160(sha256(pk))).
+pub fn bostrom_address(compressed_pubkey: &[u8]) -> String {
+    let account = Ripemd160::digest(Sha256::digest(compressed_pubkey));
+    bech32::encode(BOSTROM_HRP, account.to_base32(), Variant::Bech32)
+        .expect("bostrom HRP is valid bech32")
+}
+
+fn compressed(key: &VerifyingKey) -> Vec<u8> {
+    key.to_encoded_point(true).as_bytes().to_vec()
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    address: String,
+    secret_key_hex: String,
+}
+
+/// Local keystore holding one secp256k1 key bound to its bostrom address.
+pub struct FileKeystore {
+    address: String,
+    key: SigningKey,
+}
+
+impl FileKeystore {
+    /// Wrap a 32-byte secret; entropy is the caller's responsibility.
+    pub fn from_secret_bytes(secret: &[u8]) -> Result<Self, SigningError> {
+        let key = SigningKey::from_slice(secret).map_err(|e| SigningError::InvalidKey(e.to_string()))?;
+        let address = bostrom_address(&compressed(key.verifying_key()));
+        Ok(Self { address, key })
+    }
+
+    /// Load a keystore file, refusing it if the key does not derive the stored address.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SigningError> {
+        let file: KeystoreFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let secret = from_hex(&file.secret_key_hex)
+            .ok_or_else(|| SigningError::InvalidKey("secret_key_hex is not hex".into()))?;
+        let keystore = Self::from_secret_bytes(&secret)?;
+        if keystore.address != file.address {
+            return Err(SigningError::AddressMismatch {
+                expected: file.address,
+                derived: keystore.address,
+            });
+        }
+        Ok(keystore)
+    }
+
+    /// Write the keystore as JSON to a new file, created owner-only (0600) on Unix
+    /// before any key bytes are written. An existing file is never overwritten.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SigningError> {
+        let file = KeystoreFile {
+            address: self.address.clone(),
+            secret_key_hex: to_hex(&self.key.to_bytes()),
+        };
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut out = options.open(path)?;
+        out.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        to_hex(&compressed(self.key.verifying_key()))
+    }
+}
+
+impl ShardSigner for FileKeystore {
+    fn signer_addr(&self) -> &str {
+        &self.address
+    }
+
+    fn sign(&self, canonical: &[u8]) -> Result<ShardSignature, SigningError> {
+        let signature: Signature = self.key.sign(canonical);
+        Ok(ShardSignature {
+            signer_addr: self.address.clone(),
+            public_key_hex: self.public_key_hex(),
+            signature_hex: to_hex(&signature.to_bytes()),
+        })
+    }
+}
+
+/// Checks that the embedded key derives `signer_addr`, optionally against an allowlist.
+#[derive(Debug, Clone, Default)]
+pub struct BostromVerifier {
+    trusted: Option<BTreeSet<String>>,
+}
+
+impl BostromVerifier {
+    /// Accept any signer whose key derives its claimed address.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept only the listed bostrom addresses.
+    pub fn trusting<I, S>(addrs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            trusted: Some(addrs.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl ShardVerifier for BostromVerifier {
+    fn verify(&self, canonical: &[u8], signature: &ShardSignature) -> Result<(), SigningError> {
+        let addr = &signature.signer_addr;
+        if let Some(trusted) = &self.trusted {
+            if !trusted.contains(addr) {
+                return Err(SigningError::UntrustedSigner(addr.clone()));
+            }
+        }
+
+        let pk_bytes = from_hex(&signature.public_key_hex)
+            .ok_or_else(|| SigningError::InvalidKey("public_key_hex is not hex".into()))?;
+        let key = VerifyingKey::from_sec1_bytes(&pk_bytes).map_err(|e| SigningError::InvalidKey(e.to_string()))?;
+        let derived = bostrom_address(&compressed(&key));
+        if &derived != addr {
+            return Err(SigningError::AddressMismatch {
+                expected: addr.clone(),
+                derived,
+            });
+        }
+
+        let sig_bytes = from_hex(&signature.signature_hex).ok_or_else(|| SigningError::BadSignature(addr.clone()))?;
+        let sig = Signature::from_slice(&sig_bytes).map_err(|_| SigningError::BadSignature(addr.clone()))?;
+        key.verify(canonical, &sig)
+            .map_err(|_| SigningError::BadSignature(addr.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, ResponseShard, Triad};
+
+    fn shard() -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18...".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: 0.93, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual { vt: 0.22, coords: vec![] },
+            evidence: vec![],
+            corridor_tags: vec!["mar".into()],
+        }
+    }
+
+    #[test]
+    fn sign_and_verify_round_trip() {
+        let keystore = FileKeystore::from_secret_bytes(&[7u8; 32]).unwrap();
+        assert!(keystore.signer_addr().starts_with("bostrom1"));
+
+        let s = shard();
+        let sig = sign_shard(&keystore, &s).unwrap();
+        assert!(verify_shard(&BostromVerifier::new(), &s, &sig).is_ok());
+
+        let mut tampered = s.clone();
+        tampered.triad.risk_of_harm = 0.05;
+        assert!(matches!(
+            verify_shard(&BostromVerifier::new(), &tampered, &sig),
+            Err(SigningError::BadSignature(_))
+        ));
+    }
+
+    #[test]
+    fn verifier_binds_key_to_address() {
+        let a = FileKeystore::from_secret_bytes(&[7u8; 32]).unwrap();
+        let b = FileKeystore::from_secret_bytes(&[9u8; 32]).unwrap();
+        let s = shard();
+
+        let mut forged = sign_shard(&b, &s).unwrap();
+        forged.signer_addr = a.signer_addr().to_string();
+        assert!(matches!(
+            verify_shard(&BostromVerifier::new(), &s, &forged),
+            Err(SigningError::AddressMismatch { .. })
+        ));
+
+        let sig = sign_shard(&b, &s).unwrap();
+        assert!(matches!(
+            verify_shard(&BostromVerifier::trusting([a.signer_addr()]), &s, &sig),
+            Err(SigningError::UntrustedSigner(_))
+        ));
+    }
+
+    #[test]
+    fn keystore_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("response_shard_keystore_{}.json", std::process::id()));
+        let keystore = FileKeystore::from_secret_bytes(&[7u8; 32]).unwrap();
+        let _ = fs::remove_file(&path);
+        keystore.save(&path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        assert!(matches!(keystore.save(&path), Err(SigningError::Io(_))));
+        let loaded = FileKeystore::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.signer_addr(), keystore.signer_addr());
+    }
+}
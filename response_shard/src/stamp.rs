@@ -0,0 +1,414 @@
+//! Canonical byte encoding and content-bound hexstamps for shards. [file:3][file:6]
+//!
+//! The encoding is one `path=value` line per field in a fixed, hand-written
+//! order (never struct declaration order). Strings are length-prefixed
+//! (`<len>:<bytes>`), floats use the shortest round-trip `{:e}` form with
+//! `-0` folded to `0` (distinct f64 values never share bytes), and
+//! lists write their length before their items. The hexstamp is
+//! `0x` + SHA-256 over those bytes. A struct's own `hexstamp` field is never
+//! part of its encoding.
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::chain::ChainEntry;
+use crate::evidence::EvidenceRef;
+use crate::template::ResponseShardTemplate;
+use crate::wire::ResponseShardV1;
+use crate::{DraftAssessment, Residual, ResponseShard, RiskCoord, Triad, TriadInputs};
+
+/// Version line written at the top of every canonical encoding.
+pub const CANONICAL_V1: &str = "canonical.v1";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum StampError {
+    #[error("hexstamp `{0}` is not 0x-prefixed SHA-256 hex")]
+    Malformed(String),
+    #[error("hexstamp mismatch: expected {expected}, found {found}")]
+    Mismatch { expected: String, found: String },
+}
+
+/// Writes fields in the canonical form; see the module docs.
+pub struct CanonicalEncoder {
+    buf: Vec<u8>,
+    path: Vec<String>,
+}
+
+impl CanonicalEncoder {
+    fn new(type_tag: &str) -> Self {
+        Self {
+            buf: format!("#{} {}\n", CANONICAL_V1, type_tag).into_bytes(),
+            path: Vec::new(),
+        }
+    }
+
+    fn line(&mut self, name: &str, value: &[u8]) {
+        for segment in &self.path {
+            self.buf.extend_from_slice(segment.as_bytes());
+            self.buf.push(b'.');
+        }
+        self.buf.extend_from_slice(name.as_bytes());
+        self.buf.push(b'=');
+        self.buf.extend_from_slice(value);
+        self.buf.push(b'\n');
+    }
+
+    pub fn str(&mut self, name: &str, value: &str) {
+        let mut v = format!("{}:", value.len()).into_bytes();
+        v.extend_from_slice(value.as_bytes());
+        self.line(name, &v);
+    }
+
+    pub fn f64(&mut self, name: &str, value: f64) {
+        let value = if value == 0.0 { 0.0 } else { value };
+        let text = if value.is_nan() {
+            "NaN".to_string()
+        } else if value.is_infinite() {
+            if value > 0.0 { "+inf" } else { "-inf" }.to_string()
+        } else {
+            format!("{:e}", value)
+        };
+        self.line(name, text.as_bytes());
+    }
+
+    pub fn u64(&mut self, name: &str, value: u64) {
+        self.line(name, value.to_string().as_bytes());
+    }
+
+    pub fn bool(&mut self, name: &str, value: bool) {
+        self.line(name, if value { b"true" } else { b"false" });
+    }
+
+    pub fn strs(&mut self, name: &str, items: &[String]) {
+        self.u64(&format!("{}.len", name), items.len() as u64);
+        for (i, item) in items.iter().enumerate() {
+            self.str(&format!("{}[{}]", name, i), item);
+        }
+    }
+
+    pub fn nested<T: CanonicalEncode + ?Sized>(&mut self, name: &str, value: &T) {
+        self.path.push(name.to_string());
+        value.encode_fields(self);
+        self.path.pop();
+    }
+
+    pub fn list<T: CanonicalEncode>(&mut self, name: &str, items: &[T]) {
+        self.u64(&format!("{}.len", name), items.len() as u64);
+        for (i, item) in items.iter().enumerate() {
+            self.nested(&format!("{}[{}]", name, i), item);
+        }
+    }
+}
+
+/// A struct with a canonical encoding and therefore a hexstamp.
+pub trait CanonicalEncode {
+    /// Distinguishes encodings of different struct types.
+    const TYPE_TAG: &'static str;
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder);
+
+    fn canonical_bytes(&self) -> Vec<u8> {
+        let mut enc = CanonicalEncoder::new(Self::TYPE_TAG);
+        self.encode_fields(&mut enc);
+        enc.buf
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("0x");
+    for b in bytes {
+        out.push_str(&format!("{:02x}", b));
+    }
+    out
+}
+
+/// Inverse of `to_hex`; the `0x` prefix is optional.
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// `0x` + SHA-256 of the canonical encoding.
+pub fn hexstamp<T: CanonicalEncode + ?Sized>(value: &T) -> String {
+    to_hex(&Sha256::digest(value.canonical_bytes()))
+}
+
+/// True if `stamp` has the shape `hexstamp` produces (case-insensitive).
+pub fn is_well_formed_hexstamp(stamp: &str) -> bool {
+    stamp
+        .strip_prefix("0x")
+        .map(|h| h.len() == 64 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+        .unwrap_or(false)
+}
+
+/// Check that `stamp` was computed from exactly this content.
+pub fn verify_hexstamp<T: CanonicalEncode + ?Sized>(value: &T, stamp: &str) -> Result<(), StampError> {
+    if !is_well_formed_hexstamp(stamp) {
+        return Err(StampError::Malformed(stamp.to_string()));
+    }
+    let expected = hexstamp(value);
+    if !expected.eq_ignore_ascii_case(stamp) {
+        return Err(StampError::Mismatch {
+            expected,
+            found: stamp.to_string(),
+        });
+    }
+    Ok(())
+}
+
+impl CanonicalEncode for Triad {
+    const TYPE_TAG: &'static str = "Triad";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.f64("knowledge", self.knowledge);
+        enc.f64("eco_impact", self.eco_impact);
+        enc.f64("risk_of_harm", self.risk_of_harm);
+    }
+}
+
+impl CanonicalEncode for RiskCoord {
+    const TYPE_TAG: &'static str = "RiskCoord";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.str("var_id", &self.var_id);
+        enc.f64("value", self.value);
+        enc.f64("safe", self.safe);
+        enc.f64("gold", self.gold);
+        enc.f64("hard", self.hard);
+        enc.f64("weight", self.weight);
+    }
+}
+
+impl CanonicalEncode for Residual {
+    const TYPE_TAG: &'static str = "Residual";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.f64("vt", self.vt);
+        enc.list("coords", &self.coords);
+    }
+}
+
+impl CanonicalEncode for ResponseShard {
+    const TYPE_TAG: &'static str = "ResponseShard";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.str("user_did", &self.user_did);
+        enc.str("topic", &self.topic);
+        enc.nested("triad", &self.triad);
+        enc.nested("residual", &self.residual);
+        enc.list("evidence", &self.evidence);
+        enc.strs("corridor_tags", &self.corridor_tags);
+    }
+}
+
+impl CanonicalEncode for EvidenceRef {
+    const TYPE_TAG: &'static str = "EvidenceRef";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        match self {
+            EvidenceRef::Equation { id } => {
+                enc.str("kind", "equation");
+                enc.str("id", id);
+            }
+            EvidenceRef::CorridorRow { particle, var_id } => {
+                enc.str("kind", "corridor_row");
+                enc.str("particle", particle);
+                enc.str("var_id", var_id);
+            }
+            EvidenceRef::ShardRef { schema, hexstamp } => {
+                enc.str("kind", "shard_ref");
+                enc.str("schema", schema);
+                enc.str("hexstamp", &hexstamp.to_ascii_lowercase());
+            }
+            EvidenceRef::PilotMeasurement { var_id, value, units } => {
+                enc.str("kind", "pilot_measurement");
+                enc.str("var_id", var_id);
+                enc.f64("value", *value);
+                enc.str("units", units);
+            }
+            EvidenceRef::LabReport { lab_id, report_id } => {
+                enc.str("kind", "lab_report");
+                enc.str("lab_id", lab_id);
+                enc.str("report_id", report_id);
+            }
+            EvidenceRef::CorridorBand { var_id, units, safe, gold, hard } => {
+                enc.str("kind", "corridor_band");
+                enc.str("var_id", var_id);
+                enc.str("units", units);
+                enc.f64("safe", *safe);
+                enc.f64("gold", *gold);
+                enc.f64("hard", *hard);
+            }
+        }
+    }
+}
+
+impl CanonicalEncode for ResponseShardTemplate {
+    const TYPE_TAG: &'static str = "ResponseShardTemplate";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.str("shardid", &self.shardid);
+        enc.str("userdid", &self.userdid);
+        enc.f64("default_K_min", self.default_k_min);
+        enc.f64("default_E_min", self.default_e_min);
+        enc.f64("default_R_max", self.default_r_max);
+        enc.bool("require_KER", self.require_ker);
+        enc.bool("require_Vt", self.require_vt);
+        enc.u64("require_rx_min_count", self.require_rx_min_count as u64);
+        enc.bool("language_rust_only", self.language_rust_only);
+        enc.bool("language_aln_only", self.language_aln_only);
+        enc.bool("python_forbidden", self.python_forbidden);
+        enc.bool("unsanctioned_crypto_forbidden", self.unsanctioned_crypto_forbidden);
+        enc.bool("biodegradable_required", self.biodegradable_required);
+        enc.bool("nontoxic_required", self.nontoxic_required);
+        enc.strs("eco_domain_tags", &self.eco_domain_tags);
+    }
+}
+
+impl CanonicalEncode for TriadInputs {
+    const TYPE_TAG: &'static str = "TriadInputs";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.f64("k", self.k);
+        enc.f64("e", self.e);
+        enc.f64("r", self.r);
+    }
+}
+
+impl CanonicalEncode for DraftAssessment {
+    const TYPE_TAG: &'static str = "DraftAssessment";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.str("user_did", &self.user_did);
+        enc.str("topic", &self.topic);
+        enc.nested("base_triads", &self.base_triads);
+        enc.list("base_coords", &self.base_coords);
+        enc.list("evidence", &self.evidence);
+        enc.strs("corridor_tags", &self.corridor_tags);
+    }
+}
+
+/// Covers the wire fields; `hexstamp` is the stamp itself and is skipped.
+impl CanonicalEncode for ResponseShardV1 {
+    const TYPE_TAG: &'static str = "ResponseShardV1";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.str("userdid", &self.userdid);
+        enc.str("primary_bostrom", &self.primary_bostrom);
+        enc.str("topic", &self.topic);
+        enc.f64("knowledgefactor01", self.knowledgefactor01);
+        enc.f64("ecoimpact01", self.ecoimpact01);
+        enc.f64("riskofharm01", self.riskofharm01);
+        enc.u64("rx.len", self.rx.len() as u64);
+        for (name, value) in &self.rx {
+            enc.f64(&format!("rx[{}]", name), *value);
+        }
+        enc.f64("violationresidual", self.violationresidual);
+        enc.strs("corridortags", &self.corridortags);
+        enc.list("evidencestrings", &self.evidencestrings);
+    }
+}
+
+/// Covers the link and shard; `entry_hash` is this stamp and is skipped.
+impl CanonicalEncode for ChainEntry {
+    const TYPE_TAG: &'static str = "ChainEntry";
+
+    fn encode_fields(&self, enc: &mut CanonicalEncoder) {
+        enc.u64("index", self.index);
+        enc.str("prev_hash", &self.prev_hash);
+        enc.nested("shard", &self.shard);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard() -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: 0.93, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual::from_coords(vec![RiskCoord {
+                var_id: "r_sat".into(),
+                value: 0.3,
+                safe: 0.0,
+                gold: 0.7,
+                hard: 1.0,
+                weight: 0.4,
+            }]),
+            evidence: vec![EvidenceRef::equation("K=N_backed/N_critical")],
+            corridor_tags: vec!["mar".into()],
+        }
+    }
+
+    #[test]
+    fn encoding_is_stable_and_ordered() {
+        let text = String::from_utf8(shard().canonical_bytes()).unwrap();
+        let expected = "#canonical.v1 ResponseShard\n\
+            user_did=46:bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7\n\
+            topic=15:phoenix-mar-sat\n\
+            triad.knowledge=9.3e-1\n\
+            triad.eco_impact=9e-1\n\
+            triad.risk_of_harm=1.3e-1\n\
+            residual.vt=1.2e-1\n\
+            residual.coords.len=1\n\
+            residual.coords[0].var_id=5:r_sat\n\
+            residual.coords[0].value=3e-1\n\
+            residual.coords[0].safe=0e0\n\
+            residual.coords[0].gold=7e-1\n\
+            residual.coords[0].hard=1e0\n\
+            residual.coords[0].weight=4e-1\n\
+            evidence.len=1\n\
+            evidence[0].kind=8:equation\n\
+            evidence[0].id=21:K=N_backed/N_critical\n\
+            corridor_tags.len=1\n\
+            corridor_tags[0]=3:mar\n";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn hexstamp_binds_to_content() {
+        let s = shard();
+        let stamp = hexstamp(&s);
+        assert!(is_well_formed_hexstamp(&stamp));
+        assert_eq!(verify_hexstamp(&s, &stamp), Ok(()));
+
+        let mut changed = s.clone();
+        changed.triad.knowledge = 0.94;
+        assert!(matches!(verify_hexstamp(&changed, &stamp), Err(StampError::Mismatch { .. })));
+        assert!(matches!(
+            verify_hexstamp(&s, "0x4a3b2c1d9e8f7g6h"),
+            Err(StampError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn floats_round_trip_so_close_values_stamp_differently() {
+        let mut a = shard();
+        let mut b = shard();
+        a.residual.coords[0].value = 0.3;
+        b.residual.coords[0].value = 0.1 + 0.2;
+        assert_ne!(a.canonical_bytes(), b.canonical_bytes());
+        assert_ne!(hexstamp(&a), hexstamp(&b));
+
+        let draft = DraftAssessment {
+            user_did: a.user_did.clone(),
+            topic: a.topic.clone(),
+            base_triads: TriadInputs { k: 0.3, e: 0.9, r: 0.1 },
+            base_coords: a.residual.coords.clone(),
+            evidence: a.evidence.clone(),
+            corridor_tags: a.corridor_tags.clone(),
+        };
+        let mut nudged = draft.clone();
+        nudged.base_triads.k = 0.1 + 0.2;
+        assert_ne!(hexstamp(&draft), hexstamp(&nudged));
+    }
+}
@@ -0,0 +1,417 @@
+//! `response.shard.template.v1` and per-turn compliance grading. [file:6][file:7]
+//!
+//! Threshold and eco-tag rules are advisory (they down-rank, never block a
+//! reply); structural rules (`require_KER`, `require_Vt`,
+//! `require_rx_min_count`, `userdid`) fail. Language, crypto and material
+//! constraints are carried for downstream tooling but are not observable on a
+//! `ResponseShard`, so `check` does not grade them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::wire::{
+    check_list, check_text, join_list, split_list, AlnRow, AlnRowWriter, WireError,
+};
+use crate::ResponseShard;
+
+/// Schema identifier carried by every encoded template.
+pub const RESPONSE_SHARD_TEMPLATE_V1: &str = "response.shard.template.v1";
+
+/// One `response.shard.template.v1` particle. [file:6]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseShardTemplate {
+    pub shardid: String,
+    pub userdid: String,
+    pub hexstamp: String,
+
+    #[serde(rename = "default_K_min")]
+    pub default_k_min: f64,
+    #[serde(rename = "default_E_min")]
+    pub default_e_min: f64,
+    #[serde(rename = "default_R_max")]
+    pub default_r_max: f64,
+
+    #[serde(rename = "require_KER")]
+    pub require_ker: bool,
+    #[serde(rename = "require_Vt")]
+    pub require_vt: bool,
+    pub require_rx_min_count: u32,
+
+    pub language_rust_only: bool,
+    pub language_aln_only: bool,
+    pub python_forbidden: bool,
+    pub unsanctioned_crypto_forbidden: bool,
+
+    pub biodegradable_required: bool,
+    pub nontoxic_required: bool,
+    /// `"; "`-separated on the wire.
+    #[serde(with = "tag_list")]
+    pub eco_domain_tags: Vec<String>,
+}
+
+mod tag_list {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(tags: &[String], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&super::join_list(tags))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
+        Ok(super::split_list(&String::deserialize(d)?))
+    }
+}
+
+/// Rules graded by `ResponseShardTemplate::check`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateRule {
+    KMin,
+    EMin,
+    RMax,
+    RequireKer,
+    RequireVt,
+    RxMinCount,
+    UserDid,
+    EcoDomainTags,
+}
+
+impl TemplateRule {
+    /// Template field the rule is derived from.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TemplateRule::KMin => "default_K_min",
+            TemplateRule::EMin => "default_E_min",
+            TemplateRule::RMax => "default_R_max",
+            TemplateRule::RequireKer => "require_KER",
+            TemplateRule::RequireVt => "require_Vt",
+            TemplateRule::RxMinCount => "require_rx_min_count",
+            TemplateRule::UserDid => "userdid",
+            TemplateRule::EcoDomainTags => "eco_domain_tags",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOutcome {
+    Pass,
+    /// Not met, but response-level rules only steer research. [file:11]
+    Advisory,
+    Fail,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleResult {
+    pub rule: TemplateRule,
+    pub outcome: RuleOutcome,
+    pub detail: String,
+}
+
+/// Per-rule grading of one shard against one template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceReport {
+    pub template_id: String,
+    pub results: Vec<RuleResult>,
+}
+
+impl ComplianceReport {
+    /// True when no rule failed; advisories do not count against compliance.
+    pub fn is_compliant(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &RuleResult> {
+        self.results.iter().filter(|r| r.outcome == RuleOutcome::Fail)
+    }
+
+    pub fn advisories(&self) -> impl Iterator<Item = &RuleResult> {
+        self.results.iter().filter(|r| r.outcome == RuleOutcome::Advisory)
+    }
+
+    pub fn outcome(&self, rule: TemplateRule) -> Option<RuleOutcome> {
+        self.results.iter().find(|r| r.rule == rule).map(|r| r.outcome)
+    }
+}
+
+fn in_01(x: f64) -> bool {
+    x.is_finite() && (0.0..=1.0).contains(&x)
+}
+
+impl ResponseShardTemplate {
+    /// Recommended parameterization from `docs/response_shard.md` §4.
+    pub fn recommended(userdid: &str) -> Self {
+        Self {
+            shardid: "response_shard.md::v1".into(),
+            userdid: userdid.into(),
+            hexstamp: String::new(),
+            default_k_min: 0.93,
+            default_e_min: 0.89,
+            default_r_max: 0.15,
+            require_ker: true,
+            require_vt: true,
+            require_rx_min_count: 3,
+            language_rust_only: true,
+            language_aln_only: true,
+            python_forbidden: true,
+            unsanctioned_crypto_forbidden: true,
+            biodegradable_required: true,
+            nontoxic_required: true,
+            eco_domain_tags: [
+                "biodegradable-materials",
+                "cyboquatic",
+                "cybocindric",
+                "Phoenix-heat",
+                "MAR",
+                "circular-economy",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        }
+    }
+
+    fn check_fields(&self) -> Result<(), WireError> {
+        check_text("shardid", &self.shardid)?;
+        check_text("userdid", &self.userdid)?;
+        check_text("hexstamp", &self.hexstamp)?;
+        for (field, v) in [
+            ("default_K_min", self.default_k_min),
+            ("default_E_min", self.default_e_min),
+            ("default_R_max", self.default_r_max),
+        ] {
+            if !in_01(v) {
+                return Err(WireError::InvalidField {
+                    field: field.into(),
+                    reason: format!("{} is outside [0,1]", v),
+                });
+            }
+        }
+        check_list("eco_domain_tags", &self.eco_domain_tags)
+    }
+
+    /// Encode as JSON tagged with `"schema": "response.shard.template.v1"`.
+    pub fn to_json(&self) -> Result<String, WireError> {
+        self.check_fields()?;
+        let mut value = serde_json::to_value(self)?;
+        if let Value::Object(obj) = &mut value {
+            obj.insert("schema".into(), RESPONSE_SHARD_TEMPLATE_V1.into());
+        }
+        Ok(serde_json::to_string(&value)?)
+    }
+
+    /// Decode JSON; unknown versions and unknown fields are rejected.
+    pub fn from_json(input: &str) -> Result<Self, WireError> {
+        let mut value: Value = serde_json::from_str(input)?;
+        let schema = match &mut value {
+            Value::Object(obj) => obj.remove("schema"),
+            _ => {
+                return Err(WireError::InvalidField {
+                    field: "$".into(),
+                    reason: "expected a JSON object".into(),
+                })
+            }
+        };
+        match schema {
+            Some(Value::String(s)) if s == RESPONSE_SHARD_TEMPLATE_V1 => {}
+            Some(Value::String(s)) => return Err(WireError::UnknownVersion(s)),
+            Some(other) => return Err(WireError::UnknownVersion(other.to_string())),
+            None => return Err(WireError::MissingField("schema".into())),
+        }
+        let template: Self = serde_json::from_value(value)?;
+        template.check_fields()?;
+        Ok(template)
+    }
+
+    /// Encode as an ALN `row response.shard.template.v1 ... end` block.
+    pub fn to_aln_row(&self) -> Result<String, WireError> {
+        self.check_fields()?;
+        let mut row = AlnRowWriter::new(RESPONSE_SHARD_TEMPLATE_V1);
+        row.field("shardid", &self.shardid);
+        row.field("userdid", &self.userdid);
+        row.field("hexstamp", &self.hexstamp);
+        row.field("default_K_min", self.default_k_min);
+        row.field("default_E_min", self.default_e_min);
+        row.field("default_R_max", self.default_r_max);
+        row.field("require_KER", self.require_ker);
+        row.field("require_Vt", self.require_vt);
+        row.field("require_rx_min_count", self.require_rx_min_count);
+        row.field("language_rust_only", self.language_rust_only);
+        row.field("language_aln_only", self.language_aln_only);
+        row.field("python_forbidden", self.python_forbidden);
+        row.field("unsanctioned_crypto_forbidden", self.unsanctioned_crypto_forbidden);
+        row.field("biodegradable_required", self.biodegradable_required);
+        row.field("nontoxic_required", self.nontoxic_required);
+        row.field("eco_domain_tags", join_list(&self.eco_domain_tags));
+        Ok(row.finish())
+    }
+
+    /// Decode an ALN row produced by `to_aln_row`.
+    pub fn from_aln_row(input: &str) -> Result<Self, WireError> {
+        let mut row = AlnRow::parse(input, RESPONSE_SHARD_TEMPLATE_V1)?;
+        let template = Self {
+            shardid: row.take("shardid")?,
+            userdid: row.take("userdid")?,
+            hexstamp: row.take("hexstamp")?,
+            default_k_min: row.take_parsed("default_K_min")?,
+            default_e_min: row.take_parsed("default_E_min")?,
+            default_r_max: row.take_parsed("default_R_max")?,
+            require_ker: row.take_parsed("require_KER")?,
+            require_vt: row.take_parsed("require_Vt")?,
+            require_rx_min_count: row.take_parsed("require_rx_min_count")?,
+            language_rust_only: row.take_parsed("language_rust_only")?,
+            language_aln_only: row.take_parsed("language_aln_only")?,
+            python_forbidden: row.take_parsed("python_forbidden")?,
+            unsanctioned_crypto_forbidden: row.take_parsed("unsanctioned_crypto_forbidden")?,
+            biodegradable_required: row.take_parsed("biodegradable_required")?,
+            nontoxic_required: row.take_parsed("nontoxic_required")?,
+            eco_domain_tags: split_list(&row.take("eco_domain_tags")?),
+        };
+        if let Some(key) = row.remaining_keys().into_iter().next() {
+            return Err(WireError::UnexpectedField(key));
+        }
+        template.check_fields()?;
+        Ok(template)
+    }
+
+    /// Grade `shard` against every rule this template declares.
+    pub fn check(&self, shard: &ResponseShard) -> ComplianceReport {
+        let mut results = Vec::new();
+        let mut push = |rule, outcome, detail: String| results.push(RuleResult { rule, outcome, detail });
+        let advisory = |ok: bool| if ok { RuleOutcome::Pass } else { RuleOutcome::Advisory };
+        let hard = |ok: bool| if ok { RuleOutcome::Pass } else { RuleOutcome::Fail };
+
+        let t = &shard.triad;
+        push(
+            TemplateRule::KMin,
+            advisory(t.knowledge >= self.default_k_min),
+            format!("K = {} vs K_min = {}", t.knowledge, self.default_k_min),
+        );
+        push(
+            TemplateRule::EMin,
+            advisory(t.eco_impact >= self.default_e_min),
+            format!("E = {} vs E_min = {}", t.eco_impact, self.default_e_min),
+        );
+        push(
+            TemplateRule::RMax,
+            advisory(t.risk_of_harm <= self.default_r_max),
+            format!("R = {} vs R_max = {}", t.risk_of_harm, self.default_r_max),
+        );
+
+        if self.require_ker {
+            let ok = in_01(t.knowledge) && in_01(t.eco_impact) && in_01(t.risk_of_harm);
+            push(
+                TemplateRule::RequireKer,
+                hard(ok),
+                if ok {
+                    "K/E/R present and in [0,1]".into()
+                } else {
+                    format!("K/E/R ({}, {}, {}) not all in [0,1]", t.knowledge, t.eco_impact, t.risk_of_harm)
+                },
+            );
+        }
+
+        if self.require_vt {
+            let r = &shard.residual;
+            let ok = r.vt.is_finite() && r.vt >= 0.0 && !r.coords.is_empty();
+            push(
+                TemplateRule::RequireVt,
+                hard(ok),
+                format!("V_t = {} over {} coordinate(s)", r.vt, r.coords.len()),
+            );
+        }
+
+        let n_rx = shard.residual.coords.len();
+        push(
+            TemplateRule::RxMinCount,
+            hard(n_rx >= self.require_rx_min_count as usize),
+            format!("{} rx coordinate(s), {} required", n_rx, self.require_rx_min_count),
+        );
+
+        push(
+            TemplateRule::UserDid,
+            hard(shard.user_did == self.userdid),
+            format!("shard DID `{}`, template DID `{}`", shard.user_did, self.userdid),
+        );
+
+        if !self.eco_domain_tags.is_empty() {
+            let matched: Vec<&str> = shard
+                .corridor_tags
+                .iter()
+                .filter(|tag| self.eco_domain_tags.iter().any(|d| d.eq_ignore_ascii_case(tag)))
+                .map(String::as_str)
+                .collect();
+            push(
+                TemplateRule::EcoDomainTags,
+                advisory(!matched.is_empty()),
+                if matched.is_empty() {
+                    "no corridor tag in the template's eco domains".into()
+                } else {
+                    format!("eco domains: {}", matched.join(", "))
+                },
+            );
+        }
+
+        ComplianceReport {
+            template_id: self.shardid.clone(),
+            results,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Residual, RiskCoord, Triad};
+
+    const DID: &str = "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7";
+
+    fn shard(k: f64, n_rx: usize) -> ResponseShard {
+        let coords = ["r_factual", "r_eco", "r_social"][..n_rx]
+            .iter()
+            .map(|id| RiskCoord {
+                var_id: id.to_string(),
+                value: 0.1,
+                safe: 0.0,
+                gold: 0.7,
+                hard: 1.0,
+                weight: 0.3,
+            })
+            .collect();
+        ResponseShard {
+            user_did: DID.into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: k, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual::from_coords(coords),
+            evidence: vec![],
+            corridor_tags: vec!["mar".into()],
+        }
+    }
+
+    #[test]
+    fn json_and_aln_round_trip() {
+        let t = ResponseShardTemplate::recommended(DID);
+        let json = t.to_json().unwrap();
+        assert!(json.contains("\"default_K_min\":0.93"));
+        assert_eq!(ResponseShardTemplate::from_json(&json).unwrap(), t);
+        assert_eq!(ResponseShardTemplate::from_aln_row(&t.to_aln_row().unwrap()).unwrap(), t);
+
+        let v2 = json.replace(RESPONSE_SHARD_TEMPLATE_V1, "response.shard.template.v2");
+        assert!(matches!(ResponseShardTemplate::from_json(&v2), Err(WireError::UnknownVersion(_))));
+    }
+
+    #[test]
+    fn low_k_is_advisory_missing_rx_fails() {
+        let t = ResponseShardTemplate::recommended(DID);
+
+        let ok = t.check(&shard(0.94, 3));
+        assert!(ok.is_compliant());
+        assert_eq!(ok.advisories().count(), 0);
+
+        let low_k = t.check(&shard(0.80, 3));
+        assert!(low_k.is_compliant());
+        assert_eq!(low_k.outcome(TemplateRule::KMin), Some(RuleOutcome::Advisory));
+
+        let thin = t.check(&shard(0.94, 2));
+        assert!(!thin.is_compliant());
+        assert_eq!(thin.outcome(TemplateRule::RxMinCount), Some(RuleOutcome::Fail));
+    }
+}
@@ -0,0 +1,392 @@
+//! Trace-level Lyapunov checking over a residual time series. [file:6][file:7]
+//!
+//! `safestep` judges one prev/next pair; pilot review needs whole runs. Each
+//! sample's V_t is compared against the lowest V_t of the `window` samples
+//! before it plus `noise_eps`, so jitter of ε over k samples is tolerated
+//! while a sustained climb is not. Samples inside the safe interior never
+//! count. An excursion opens on the first counted increase and closes when
+//! V_t falls back to within `noise_eps` of the level it left.
+
+use thiserror::Error;
+
+use crate::{Residual, RiskCoord};
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TraceError {
+    #[error("window must be at least 1 sample and eps finite and >= 0")]
+    InvalidPolicy,
+    #[error("sample {index} at t={t} is earlier than t={prev}")]
+    OutOfOrder { index: usize, prev: u64, t: u64 },
+    #[error("sample {index} at t={t} has a non-finite `{var_id}`")]
+    NonFinite { index: usize, t: u64, var_id: String },
+    #[error("trace CSV line {line}: {reason}")]
+    Csv { line: usize, reason: String },
+}
+
+/// One residual with its timestamp, seconds since the epoch.
+#[derive(Debug, Clone)]
+pub struct TracePoint {
+    pub t: u64,
+    pub residual: Residual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TracePolicy {
+    /// Same meaning as `SafeStepPolicy::safe_interior_eps`.
+    pub safe_interior_eps: f64,
+    /// Rise over the window minimum tolerated as noise.
+    pub noise_eps: f64,
+    /// Samples looked back for the reference V_t; 1 is plain `safestep`.
+    pub window: usize,
+}
+
+impl Default for TracePolicy {
+    fn default() -> Self {
+        Self {
+            safe_interior_eps: 0.0,
+            noise_eps: 0.0,
+            window: 1,
+        }
+    }
+}
+
+/// A counted V_t increase at sample `index`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VtIncrease {
+    pub index: usize,
+    pub t: u64,
+    /// Lowest V_t in the window before `index`.
+    pub reference: f64,
+    pub vt: f64,
+    /// `vt - reference - noise_eps`, always > 0.
+    pub excess: f64,
+}
+
+/// A stretch where V_t stayed above the level it left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Excursion {
+    pub start: usize,
+    /// First sample back at baseline; `None` if the trace ends first.
+    pub end: Option<usize>,
+    pub start_t: u64,
+    pub end_t: u64,
+    pub baseline: f64,
+    pub peak_vt: f64,
+}
+
+impl Excursion {
+    pub fn samples(&self, trace_len: usize) -> usize {
+        self.end.unwrap_or(trace_len) - self.start
+    }
+
+    pub fn recovered(&self) -> bool {
+        self.end.is_some()
+    }
+}
+
+/// First r_x past its hard limit (or below 0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceHardBreach {
+    pub index: usize,
+    pub t: u64,
+    pub var_id: String,
+    pub value: f64,
+    pub hard: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceReport {
+    pub samples: usize,
+    pub increases: Vec<VtIncrease>,
+    pub excursions: Vec<Excursion>,
+    /// Σ excess over `increases`.
+    pub cumulative_excess: f64,
+    pub first_hard_breach: Option<TraceHardBreach>,
+}
+
+impl TraceReport {
+    /// Longest excursion by sample count; the earliest wins a tie.
+    pub fn longest_excursion(&self) -> Option<&Excursion> {
+        let n = self.samples;
+        self.excursions.iter().rev().max_by_key(|e| e.samples(n))
+    }
+
+    pub fn is_monotone(&self) -> bool {
+        self.increases.is_empty()
+    }
+}
+
+fn in_interior(residual: &Residual, eps: f64) -> bool {
+    residual.coords.iter().all(|c| c.value <= c.safe + eps)
+}
+
+/// Scan `points` in order; timestamps must not go backwards and every r_x and
+/// V_t must be finite, since a NaN sample would otherwise read as monotone.
+pub fn check_trace(points: &[TracePoint], policy: &TracePolicy) -> Result<TraceReport, TraceError> {
+    let eps_ok = |e: f64| e.is_finite() && e >= 0.0;
+    if policy.window == 0 || !eps_ok(policy.noise_eps) || !eps_ok(policy.safe_interior_eps) {
+        return Err(TraceError::InvalidPolicy);
+    }
+
+    let mut report = TraceReport {
+        samples: points.len(),
+        increases: Vec::new(),
+        excursions: Vec::new(),
+        cumulative_excess: 0.0,
+        first_hard_breach: None,
+    };
+    let mut open: Option<Excursion> = None;
+
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 && p.t < points[i - 1].t {
+            return Err(TraceError::OutOfOrder {
+                index: i,
+                prev: points[i - 1].t,
+                t: p.t,
+            });
+        }
+        let non_finite = p
+            .residual
+            .coords
+            .iter()
+            .find(|c| !c.value.is_finite())
+            .map(|c| c.var_id.clone())
+            .or_else(|| (!p.residual.vt.is_finite()).then(|| "V_t".to_string()));
+        if let Some(var_id) = non_finite {
+            return Err(TraceError::NonFinite { index: i, t: p.t, var_id });
+        }
+        if report.first_hard_breach.is_none() {
+            if let Some(c) = p
+                .residual
+                .coords
+                .iter()
+                .find(|c| c.value > c.hard || c.value < 0.0)
+            {
+                report.first_hard_breach = Some(TraceHardBreach {
+                    index: i,
+                    t: p.t,
+                    var_id: c.var_id.clone(),
+                    value: c.value,
+                    hard: c.hard,
+                });
+            }
+        }
+
+        let vt = p.residual.vt;
+        let interior = in_interior(&p.residual, policy.safe_interior_eps);
+        if let Some(e) = open.as_mut() {
+            if interior || vt <= e.baseline + policy.noise_eps {
+                e.end = Some(i);
+                e.end_t = p.t;
+                report.excursions.push(*e);
+                open = None;
+            } else {
+                e.peak_vt = e.peak_vt.max(vt);
+                e.end_t = p.t;
+            }
+        }
+        if i == 0 || interior {
+            continue;
+        }
+
+        let reference = points[i.saturating_sub(policy.window)..i]
+            .iter()
+            .map(|q| q.residual.vt)
+            .fold(f64::INFINITY, f64::min);
+        let excess = vt - reference - policy.noise_eps;
+        if excess > 0.0 {
+            report.increases.push(VtIncrease {
+                index: i,
+                t: p.t,
+                reference,
+                vt,
+                excess,
+            });
+            report.cumulative_excess += excess;
+            if open.is_none() {
+                open = Some(Excursion {
+                    start: i,
+                    end: None,
+                    start_t: p.t,
+                    end_t: p.t,
+                    baseline: reference,
+                    peak_vt: vt,
+                });
+            }
+        }
+    }
+    report.excursions.extend(open);
+    Ok(report)
+}
+
+/// Read a trace CSV with header `t,<var_id>,...` of r_x values; bands and weights
+/// come from the matching `template` coordinate.
+pub fn read_trace_csv(input: &str, template: &[RiskCoord]) -> Result<Vec<TracePoint>, TraceError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+    let (header_line, header) = lines.next().ok_or(TraceError::Csv {
+        line: 1,
+        reason: "missing header".into(),
+    })?;
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    if header.first() != Some(&"t") {
+        return Err(TraceError::Csv {
+            line: header_line,
+            reason: "first column must be `t`".into(),
+        });
+    }
+    let columns = header[1..]
+        .iter()
+        .map(|var_id| {
+            template
+                .iter()
+                .find(|c| c.var_id == *var_id)
+                .ok_or_else(|| TraceError::Csv {
+                    line: header_line,
+                    reason: format!("no corridor for `{}`", var_id),
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut points = Vec::new();
+    for (line, text) in lines {
+        let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+        if cells.len() != header.len() {
+            return Err(TraceError::Csv {
+                line,
+                reason: format!("expected {} cells, found {}", header.len(), cells.len()),
+            });
+        }
+        let bad = |col: usize| TraceError::Csv {
+            line,
+            reason: format!("`{}` = `{}` is not a finite number", header[col], cells[col]),
+        };
+        let t = cells[0].parse().map_err(|_| bad(0))?;
+        let mut coords = Vec::with_capacity(columns.len());
+        for (col, band) in columns.iter().enumerate() {
+            let value: f64 = cells[col + 1].parse().map_err(|_| bad(col + 1))?;
+            if !value.is_finite() {
+                return Err(bad(col + 1));
+            }
+            coords.push(RiskCoord {
+                value,
+                ..(*band).clone()
+            });
+        }
+        points.push(TracePoint {
+            t,
+            residual: Residual::from_coords(coords),
+        });
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> Vec<RiskCoord> {
+        vec![RiskCoord {
+            var_id: "r_sat".into(),
+            value: 0.0,
+            safe: 0.05,
+            gold: 0.5,
+            hard: 1.0,
+            weight: 1.0,
+        }]
+    }
+
+    fn trace(values: &[f64]) -> Vec<TracePoint> {
+        let rows: Vec<String> = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{},{}", i * 3600, v))
+            .collect();
+        read_trace_csv(&format!("t,r_sat\n{}\n", rows.join("\n")), &template()).unwrap()
+    }
+
+    #[test]
+    fn window_tolerates_noise_but_not_a_climb() {
+        let jitter = trace(&[0.40, 0.41, 0.39, 0.40, 0.38]);
+        assert_eq!(
+            check_trace(&jitter, &TracePolicy::default())
+                .unwrap()
+                .increases
+                .len(),
+            2
+        );
+        let tolerant = TracePolicy {
+            noise_eps: 0.02,
+            window: 3,
+            ..Default::default()
+        };
+        assert!(check_trace(&jitter, &tolerant).unwrap().is_monotone());
+
+        let climb = trace(&[0.40, 0.41, 0.42, 0.43, 0.44]);
+        let report = check_trace(&climb, &tolerant).unwrap();
+        assert_eq!(report.increases[0].index, 3);
+        assert!(!report.longest_excursion().unwrap().recovered());
+    }
+
+    #[test]
+    fn reports_excursions_excess_and_first_hard_breach() {
+        let points = trace(&[0.30, 0.35, 0.50, 0.32, 0.29, 0.60, 1.20, 0.70, 0.65, 0.50]);
+        let report = check_trace(&points, &TracePolicy::default()).unwrap();
+
+        let idx: Vec<usize> = report.increases.iter().map(|i| i.index).collect();
+        assert_eq!(idx, [1, 2, 5, 6]);
+        assert!((report.cumulative_excess - (0.05 + 0.15 + 0.31 + 0.60)).abs() < 1e-9);
+        assert_eq!(report.excursions.len(), 2);
+        // 0.35 → 0.32 sits above the 0.30 it left; the first excursion closes at 0.29.
+        assert_eq!(
+            (report.excursions[0].start, report.excursions[0].end),
+            (1, Some(4))
+        );
+        let longest = report.longest_excursion().unwrap();
+        assert_eq!(
+            (longest.start, longest.end, longest.peak_vt),
+            (5, None, 1.20)
+        );
+        assert_eq!(longest.start_t, 5 * 3600);
+
+        let breach = report.first_hard_breach.unwrap();
+        assert_eq!((breach.index, breach.var_id.as_str()), (6, "r_sat"));
+
+        let mut shuffled = points.clone();
+        shuffled.swap(3, 4);
+        shuffled[3].t = 100;
+        assert_eq!(
+            check_trace(&shuffled, &TracePolicy::default()),
+            Err(TraceError::OutOfOrder {
+                index: 3,
+                prev: 7200,
+                t: 100
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_non_finite_samples() {
+        let csv = "t,r_sat\n0,0.30\n3600,NaN\n";
+        assert!(matches!(
+            read_trace_csv(csv, &template()),
+            Err(TraceError::Csv { line: 3, .. })
+        ));
+
+        let mut points = trace(&[0.30, 0.35]);
+        points[1].residual.coords[0].value = f64::NAN;
+        points[1].residual.vt = f64::NAN;
+        assert_eq!(
+            check_trace(&points, &TracePolicy::default()),
+            Err(TraceError::NonFinite {
+                index: 1,
+                t: 3600,
+                var_id: "r_sat".into()
+            })
+        );
+    }
+}
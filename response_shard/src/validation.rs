@@ -0,0 +1,263 @@
+//! Validated construction of triads, risk coordinates and shards. [file:6][file:7]
+
+use thiserror::Error;
+
+use crate::{Residual, ResponseShard, RiskCoord, Triad};
+
+/// A single violation found while validating shard data.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("`{field}` = {value} is not finite")]
+    NonFinite { field: String, value: f64 },
+    #[error("`{field}` = {value} is outside [0,1]")]
+    OutOfUnitRange { field: String, value: f64 },
+    #[error("`{var_id}` bands are inconsistent: safe {safe} <= gold {gold} <= hard {hard} does not hold")]
+    InconsistentBands {
+        var_id: String,
+        safe: f64,
+        gold: f64,
+        hard: f64,
+    },
+    #[error("`{var_id}` has negative weight {weight}")]
+    NegativeWeight { var_id: String, weight: f64 },
+    #[error("`{var_id}` appears more than once in the residual")]
+    DuplicateCoord { var_id: String },
+    #[error("`{field}` is empty")]
+    Empty { field: String },
+    #[error("evidence[{index}] is invalid: {reason}")]
+    InvalidEvidence { index: usize, reason: String },
+}
+
+/// Every violation found in one validation pass, in field order.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("{} validation error(s): {}", .0.len(), join(.0))]
+pub struct ValidationErrors(pub Vec<ValidationError>);
+
+fn join(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl ValidationErrors {
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.0
+    }
+
+    fn from_vec(errors: Vec<ValidationError>) -> Result<(), Self> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Self(errors))
+        }
+    }
+}
+
+fn check_unit(field: &str, value: f64, out: &mut Vec<ValidationError>) {
+    if !value.is_finite() {
+        out.push(ValidationError::NonFinite { field: field.into(), value });
+    } else if !(0.0..=1.0).contains(&value) {
+        out.push(ValidationError::OutOfUnitRange { field: field.into(), value });
+    }
+}
+
+fn check_finite(field: &str, value: f64, out: &mut Vec<ValidationError>) -> bool {
+    if value.is_finite() {
+        true
+    } else {
+        out.push(ValidationError::NonFinite { field: field.into(), value });
+        false
+    }
+}
+
+impl Triad {
+    /// Build a triad, requiring K, E and R to be finite and in [0,1].
+    pub fn new(knowledge: f64, eco_impact: f64, risk_of_harm: f64) -> Result<Self, ValidationErrors> {
+        let triad = Self { knowledge, eco_impact, risk_of_harm };
+        triad.validate()?;
+        Ok(triad)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut out = Vec::new();
+        self.collect_violations("triad", &mut out);
+        ValidationErrors::from_vec(out)
+    }
+
+    fn collect_violations(&self, prefix: &str, out: &mut Vec<ValidationError>) {
+        check_unit(&format!("{}.knowledge", prefix), self.knowledge, out);
+        check_unit(&format!("{}.eco_impact", prefix), self.eco_impact, out);
+        check_unit(&format!("{}.risk_of_harm", prefix), self.risk_of_harm, out);
+    }
+}
+
+impl RiskCoord {
+    /// Build a coordinate with r_x in [0,1], ordered bands and a non-negative weight.
+    pub fn new(
+        var_id: impl Into<String>,
+        value: f64,
+        safe: f64,
+        gold: f64,
+        hard: f64,
+        weight: f64,
+    ) -> Result<Self, ValidationErrors> {
+        let coord = Self {
+            var_id: var_id.into(),
+            value,
+            safe,
+            gold,
+            hard,
+            weight,
+        };
+        coord.validate()?;
+        Ok(coord)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut out = Vec::new();
+        self.collect_violations(&mut out);
+        ValidationErrors::from_vec(out)
+    }
+
+    fn collect_violations(&self, out: &mut Vec<ValidationError>) {
+        if self.var_id.is_empty() {
+            out.push(ValidationError::Empty { field: "var_id".into() });
+        }
+        let id = &self.var_id;
+        check_unit(&format!("{}.value", id), self.value, out);
+
+        let bands_finite = [("safe", self.safe), ("gold", self.gold), ("hard", self.hard)]
+            .into_iter()
+            .fold(true, |ok, (name, v)| check_finite(&format!("{}.{}", id, name), v, out) && ok);
+        if bands_finite && !(self.safe <= self.gold && self.gold <= self.hard && self.safe < self.hard) {
+            out.push(ValidationError::InconsistentBands {
+                var_id: id.clone(),
+                safe: self.safe,
+                gold: self.gold,
+                hard: self.hard,
+            });
+        }
+
+        if check_finite(&format!("{}.weight", id), self.weight, out) && self.weight < 0.0 {
+            out.push(ValidationError::NegativeWeight {
+                var_id: id.clone(),
+                weight: self.weight,
+            });
+        }
+    }
+}
+
+impl Residual {
+    /// Validate every coordinate, then compute V_t = Σ w_j r_j.
+    pub fn try_from_coords(coords: Vec<RiskCoord>) -> Result<Self, ValidationErrors> {
+        let residual = Self::from_coords(coords);
+        residual.validate()?;
+        Ok(residual)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut out = Vec::new();
+        self.collect_violations(&mut out);
+        ValidationErrors::from_vec(out)
+    }
+
+    fn collect_violations(&self, out: &mut Vec<ValidationError>) {
+        check_finite("residual.vt", self.vt, out);
+        for (i, c) in self.coords.iter().enumerate() {
+            c.collect_violations(out);
+            if !c.var_id.is_empty() && self.coords[..i].iter().any(|p| p.var_id == c.var_id) {
+                out.push(ValidationError::DuplicateCoord { var_id: c.var_id.clone() });
+            }
+        }
+    }
+}
+
+impl ResponseShard {
+    /// Check the whole shard and report every violation at once.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut out = Vec::new();
+        if self.user_did.trim().is_empty() {
+            out.push(ValidationError::Empty { field: "user_did".into() });
+        }
+        if self.topic.trim().is_empty() {
+            out.push(ValidationError::Empty { field: "topic".into() });
+        }
+        self.triad.collect_violations("triad", &mut out);
+        self.residual.collect_violations(&mut out);
+        for (index, evidence) in self.evidence.iter().enumerate() {
+            if let Err(reason) = evidence.validate() {
+                out.push(ValidationError::InvalidEvidence { index, reason });
+            }
+        }
+        ValidationErrors::from_vec(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triad_rejects_out_of_range_and_nan() {
+        assert!(Triad::new(0.93, 0.90, 0.13).is_ok());
+        let err = Triad::new(1.7, f64::NAN, 0.1).unwrap_err();
+        assert_eq!(err.errors().len(), 2);
+        assert_eq!(
+            err.errors()[0],
+            ValidationError::OutOfUnitRange { field: "triad.knowledge".into(), value: 1.7 }
+        );
+        assert!(matches!(
+            &err.errors()[1],
+            ValidationError::NonFinite { field, .. } if field == "triad.eco_impact"
+        ));
+    }
+
+    #[test]
+    fn risk_coord_checks_bands_and_weight() {
+        assert!(RiskCoord::new("r_sat", 0.3, 0.0, 0.7, 1.0, 0.4).is_ok());
+        let err = RiskCoord::new("r_sat", 0.3, 0.9, 0.7, 0.5, -0.1).unwrap_err();
+        assert!(matches!(err.errors()[0], ValidationError::InconsistentBands { .. }));
+        assert!(matches!(err.errors()[1], ValidationError::NegativeWeight { .. }));
+    }
+
+    #[test]
+    fn shard_validate_lists_every_violation() {
+        let shard = ResponseShard {
+            user_did: "".into(),
+            topic: "phoenix-mar-sat".into(),
+            triad: Triad { knowledge: 1.2, eco_impact: 0.9, risk_of_harm: 0.1 },
+            residual: Residual::from_coords(vec![
+                RiskCoord {
+                    var_id: "r_pfas".into(),
+                    value: f64::NAN,
+                    safe: 0.0,
+                    gold: 0.7,
+                    hard: 1.0,
+                    weight: 0.4,
+                },
+                RiskCoord {
+                    var_id: "r_pfas".into(),
+                    value: 0.2,
+                    safe: 0.0,
+                    gold: 0.7,
+                    hard: 1.0,
+                    weight: 0.4,
+                },
+            ]),
+            evidence: vec![],
+            corridor_tags: vec![],
+        };
+        let err = shard.validate().unwrap_err();
+        let errors = err.errors();
+        assert_eq!(errors[0], ValidationError::Empty { field: "user_did".into() });
+        assert!(matches!(errors[1], ValidationError::OutOfUnitRange { .. }));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::DuplicateCoord { var_id } if var_id == "r_pfas")));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::NonFinite { field, .. } if field == "r_pfas.value")));
+    }
+}
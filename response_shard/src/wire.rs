@@ -0,0 +1,594 @@
+//! `response.shard.v1` wire format (JSON object and ALN row). [file:6][file:11]
+//!
+//! Field names follow `aln/response.shard.schema.v1.aln` exactly. Every
+//! normalized coordinate r_x is carried as an `rx_<name>01` field, so
+//! `r_factual` travels as `rx_factual01`. Corridor bands and weights are not
+//! part of the v1 particle: decoded coords come back in name order with
+//! normalized bands (safe 0.0, gold 0.7, hard 1.0) and weight 0.0, and
+//! `violationresidual` is kept verbatim as V_t.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::evidence::EvidenceRef;
+use crate::stamp::hexstamp;
+use crate::{Residual, ResponseShard, RiskCoord, Triad};
+
+/// Schema identifier carried by every encoded shard.
+pub const RESPONSE_SHARD_V1: &str = "response.shard.v1";
+
+/// Separator used by the `corridortags` and `evidencestrings` list fields.
+const LIST_SEP: char = ';';
+
+#[derive(Debug, Error)]
+pub enum WireError {
+    #[error("unknown shard schema version `{0}` (expected `{RESPONSE_SHARD_V1}`)")]
+    UnknownVersion(String),
+    #[error("missing field `{0}`")]
+    MissingField(String),
+    #[error("unexpected field `{0}`")]
+    UnexpectedField(String),
+    #[error("field `{field}` is invalid: {reason}")]
+    InvalidField { field: String, reason: String },
+    #[error("malformed ALN row at line {line}: {reason}")]
+    MalformedRow { line: usize, reason: String },
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub(crate) fn invalid(field: &str, reason: impl Into<String>) -> WireError {
+    WireError::InvalidField {
+        field: field.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// One `response.shard.v1` particle, field-for-field. [file:6]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseShardV1 {
+    pub userdid: String,
+    pub primary_bostrom: String,
+    pub topic: String,
+    pub knowledgefactor01: f64,
+    pub ecoimpact01: f64,
+    pub riskofharm01: f64,
+    /// Coordinate values keyed by name, emitted as `rx_<name>01`.
+    pub rx: BTreeMap<String, f64>,
+    pub violationresidual: f64,
+    pub corridortags: Vec<String>,
+    pub evidencestrings: Vec<EvidenceRef>,
+    pub hexstamp: String,
+}
+
+/// Maps a coordinate id (`r_factual`, `rx_factual`, `factual`) to its rx name.
+fn rx_name(var_id: &str) -> &str {
+    var_id
+        .strip_prefix("rx_")
+        .or_else(|| var_id.strip_prefix("r_"))
+        .unwrap_or(var_id)
+}
+
+fn rx_key(name: &str) -> String {
+    format!("rx_{}01", name)
+}
+
+/// Inverse of `rx_key`; `None` for keys outside the rx family.
+fn rx_name_from_key(key: &str) -> Option<&str> {
+    key.strip_prefix("rx_")
+        .and_then(|k| k.strip_suffix("01"))
+        .filter(|k| !k.is_empty())
+}
+
+pub(crate) fn check_text(field: &str, value: &str) -> Result<(), WireError> {
+    if value.contains('\n') || value.contains('\r') {
+        return Err(invalid(field, "must not contain line breaks"));
+    }
+    // ALN values are trimmed on parse, so surrounding whitespace would not round-trip.
+    if value.trim() != value {
+        return Err(invalid(field, "must not start or end with whitespace"));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_list(field: &str, items: &[String]) -> Result<(), WireError> {
+    for item in items {
+        check_text(field, item)?;
+        if item.contains(LIST_SEP) || item.trim() != item || item.is_empty() {
+            return Err(invalid(
+                field,
+                format!("item `{}` cannot be carried in a `;`-separated list", item),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_finite(field: &str, value: f64) -> Result<(), WireError> {
+    if !value.is_finite() {
+        return Err(invalid(field, format!("non-finite value {}", value)));
+    }
+    Ok(())
+}
+
+pub(crate) fn join_list(items: &[String]) -> String {
+    items.join(&format!("{} ", LIST_SEP))
+}
+
+pub(crate) fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(LIST_SEP)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn join_evidence(items: &[EvidenceRef]) -> String {
+    let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+    join_list(&items)
+}
+
+fn split_evidence(value: &str) -> Result<Vec<EvidenceRef>, WireError> {
+    split_list(value)
+        .iter()
+        .map(|s| s.parse().map_err(|e: crate::evidence::EvidenceError| invalid("evidencestrings", e.to_string())))
+        .collect()
+}
+
+impl ResponseShardV1 {
+    /// Build the wire particle for `shard`, stamped with `hexstamp`.
+    pub fn from_shard(shard: &ResponseShard, hexstamp: &str) -> Result<Self, WireError> {
+        let mut rx = BTreeMap::new();
+        for c in &shard.residual.coords {
+            let name = rx_name(&c.var_id);
+            if name.is_empty() {
+                return Err(invalid("rx", format!("coordinate id `{}` is empty", c.var_id)));
+            }
+            if rx.insert(name.to_string(), c.value).is_some() {
+                return Err(invalid(&rx_key(name), "duplicate coordinate"));
+            }
+        }
+
+        let wire = Self {
+            userdid: shard.user_did.clone(),
+            primary_bostrom: shard.user_did.clone(),
+            topic: shard.topic.clone(),
+            knowledgefactor01: shard.triad.knowledge,
+            ecoimpact01: shard.triad.eco_impact,
+            riskofharm01: shard.triad.risk_of_harm,
+            rx,
+            violationresidual: shard.residual.vt,
+            corridortags: shard.corridor_tags.clone(),
+            evidencestrings: shard.evidence.clone(),
+            hexstamp: hexstamp.to_string(),
+        };
+        wire.check()?;
+        Ok(wire)
+    }
+
+    /// Build the wire particle with a hexstamp computed from the shard's canonical encoding.
+    /// The stamp covers bands and weights too, so verify it against the original shard.
+    pub fn stamped(shard: &ResponseShard) -> Result<Self, WireError> {
+        Self::from_shard(shard, &hexstamp(shard))
+    }
+
+    /// Rebuild a `ResponseShard`; see the module docs for what v1 does not carry.
+    pub fn into_shard(self) -> ResponseShard {
+        let coords = self
+            .rx
+            .into_iter()
+            .map(|(name, value)| RiskCoord {
+                var_id: format!("r_{}", name),
+                value,
+                safe: 0.0,
+                gold: 0.7,
+                hard: 1.0,
+                weight: 0.0,
+            })
+            .collect();
+
+        ResponseShard {
+            user_did: self.userdid,
+            topic: self.topic,
+            triad: Triad {
+                knowledge: self.knowledgefactor01,
+                eco_impact: self.ecoimpact01,
+                risk_of_harm: self.riskofharm01,
+            },
+            residual: Residual {
+                vt: self.violationresidual,
+                coords,
+            },
+            evidence: self.evidencestrings,
+            corridor_tags: self.corridortags,
+        }
+    }
+
+    /// Checks that every field can be written and read back unchanged.
+    fn check(&self) -> Result<(), WireError> {
+        check_text("userdid", &self.userdid)?;
+        check_text("primary_bostrom", &self.primary_bostrom)?;
+        check_text("topic", &self.topic)?;
+        check_text("hexstamp", &self.hexstamp)?;
+        check_finite("knowledgefactor01", self.knowledgefactor01)?;
+        check_finite("ecoimpact01", self.ecoimpact01)?;
+        check_finite("riskofharm01", self.riskofharm01)?;
+        check_finite("violationresidual", self.violationresidual)?;
+        for (name, value) in &self.rx {
+            if name.chars().any(char::is_whitespace) {
+                return Err(invalid(&rx_key(name), "coordinate name contains whitespace"));
+            }
+            check_finite(&rx_key(name), *value)?;
+        }
+        check_list("corridortags", &self.corridortags)?;
+        for evidence in &self.evidencestrings {
+            evidence
+                .validate()
+                .map_err(|reason| invalid("evidencestrings", reason))?;
+        }
+        Ok(())
+    }
+
+    /// Encode as a JSON object tagged with `"schema": "response.shard.v1"`.
+    pub fn to_json(&self) -> Result<String, WireError> {
+        self.check()?;
+
+        let mut obj = Map::new();
+        obj.insert("schema".into(), RESPONSE_SHARD_V1.into());
+        obj.insert("userdid".into(), self.userdid.clone().into());
+        obj.insert("primary_bostrom".into(), self.primary_bostrom.clone().into());
+        obj.insert("topic".into(), self.topic.clone().into());
+        obj.insert("knowledgefactor01".into(), self.knowledgefactor01.into());
+        obj.insert("ecoimpact01".into(), self.ecoimpact01.into());
+        obj.insert("riskofharm01".into(), self.riskofharm01.into());
+        for (name, value) in &self.rx {
+            obj.insert(rx_key(name), (*value).into());
+        }
+        obj.insert("violationresidual".into(), self.violationresidual.into());
+        obj.insert("corridortags".into(), join_list(&self.corridortags).into());
+        obj.insert("evidencestrings".into(), join_evidence(&self.evidencestrings).into());
+        obj.insert("hexstamp".into(), self.hexstamp.clone().into());
+
+        Ok(serde_json::to_string(&Value::Object(obj))?)
+    }
+
+    /// Decode a JSON object; unknown versions and unknown fields are rejected.
+    pub fn from_json(input: &str) -> Result<Self, WireError> {
+        let value: Value = serde_json::from_str(input)?;
+        let Value::Object(mut obj) = value else {
+            return Err(invalid("$", "expected a JSON object"));
+        };
+
+        match obj.remove("schema") {
+            Some(Value::String(s)) if s == RESPONSE_SHARD_V1 => {}
+            Some(Value::String(s)) => return Err(WireError::UnknownVersion(s)),
+            Some(other) => return Err(WireError::UnknownVersion(other.to_string())),
+            None => return Err(WireError::MissingField("schema".into())),
+        }
+
+        fn take_str(obj: &mut Map<String, Value>, field: &str) -> Result<String, WireError> {
+            match obj.remove(field) {
+                Some(Value::String(s)) => Ok(s),
+                Some(_) => Err(invalid(field, "expected a string")),
+                None => Err(WireError::MissingField(field.into())),
+            }
+        }
+        fn take_f64(obj: &mut Map<String, Value>, field: &str) -> Result<f64, WireError> {
+            match obj.remove(field) {
+                Some(v) => v.as_f64().ok_or_else(|| invalid(field, "expected a number")),
+                None => Err(WireError::MissingField(field.into())),
+            }
+        }
+
+        let mut wire = Self {
+            userdid: take_str(&mut obj, "userdid")?,
+            primary_bostrom: take_str(&mut obj, "primary_bostrom")?,
+            topic: take_str(&mut obj, "topic")?,
+            knowledgefactor01: take_f64(&mut obj, "knowledgefactor01")?,
+            ecoimpact01: take_f64(&mut obj, "ecoimpact01")?,
+            riskofharm01: take_f64(&mut obj, "riskofharm01")?,
+            rx: BTreeMap::new(),
+            violationresidual: take_f64(&mut obj, "violationresidual")?,
+            corridortags: split_list(&take_str(&mut obj, "corridortags")?),
+            evidencestrings: split_evidence(&take_str(&mut obj, "evidencestrings")?)?,
+            hexstamp: take_str(&mut obj, "hexstamp")?,
+        };
+
+        let rx_keys: Vec<String> = obj.keys().cloned().collect();
+        for key in rx_keys {
+            let name = rx_name_from_key(&key)
+                .ok_or_else(|| WireError::UnexpectedField(key.clone()))?
+                .to_string();
+            let value = take_f64(&mut obj, &key)?;
+            wire.rx.insert(name, value);
+        }
+
+        wire.check()?;
+        Ok(wire)
+    }
+
+    /// Encode as an ALN row: one `field value` pair per line, in schema order.
+    pub fn to_aln_row(&self) -> Result<String, WireError> {
+        self.check()?;
+
+        let mut row = AlnRowWriter::new(RESPONSE_SHARD_V1);
+        row.field("userdid", &self.userdid);
+        row.field("primary_bostrom", &self.primary_bostrom);
+        row.field("topic", &self.topic);
+        row.field("knowledgefactor01", self.knowledgefactor01);
+        row.field("ecoimpact01", self.ecoimpact01);
+        row.field("riskofharm01", self.riskofharm01);
+        for (name, value) in &self.rx {
+            row.field(&rx_key(name), value);
+        }
+        row.field("violationresidual", self.violationresidual);
+        row.field("corridortags", join_list(&self.corridortags));
+        row.field("evidencestrings", join_evidence(&self.evidencestrings));
+        row.field("hexstamp", &self.hexstamp);
+        Ok(row.finish())
+    }
+
+    /// Decode an ALN row produced by `to_aln_row` (blank and `#` lines are skipped).
+    pub fn from_aln_row(input: &str) -> Result<Self, WireError> {
+        let mut row = AlnRow::parse(input, RESPONSE_SHARD_V1)?;
+
+        let mut wire = Self {
+            userdid: row.take("userdid")?,
+            primary_bostrom: row.take("primary_bostrom")?,
+            topic: row.take("topic")?,
+            knowledgefactor01: row.take_parsed("knowledgefactor01")?,
+            ecoimpact01: row.take_parsed("ecoimpact01")?,
+            riskofharm01: row.take_parsed("riskofharm01")?,
+            rx: BTreeMap::new(),
+            violationresidual: row.take_parsed("violationresidual")?,
+            corridortags: split_list(&row.take("corridortags")?),
+            evidencestrings: split_evidence(&row.take("evidencestrings")?)?,
+            hexstamp: row.take("hexstamp")?,
+        };
+
+        for key in row.remaining_keys() {
+            let name = rx_name_from_key(&key).ok_or_else(|| WireError::UnexpectedField(key.clone()))?;
+            wire.rx.insert(name.to_string(), row.take_parsed(&key)?);
+        }
+
+        wire.check()?;
+        Ok(wire)
+    }
+}
+
+/// Writer for the `row <particle> ... end` form shared by the v1 particles.
+#[derive(Debug)]
+pub struct AlnRowWriter {
+    out: String,
+}
+
+impl AlnRowWriter {
+    pub fn new(particle: &str) -> Self {
+        Self {
+            out: format!("row {}\n", particle),
+        }
+    }
+
+    pub fn field(&mut self, name: &str, value: impl std::fmt::Display) {
+        let value = value.to_string();
+        self.out.push_str("  ");
+        self.out.push_str(name);
+        if !value.is_empty() {
+            self.out.push(' ');
+            self.out.push_str(&value);
+        }
+        self.out.push('\n');
+    }
+
+    pub fn finish(mut self) -> String {
+        self.out.push_str("end\n");
+        self.out
+    }
+}
+
+/// Parsed `row <particle> ... end` block; fields are consumed with `take`.
+#[derive(Debug, Clone)]
+pub struct AlnRow {
+    fields: Vec<(String, String)>,
+}
+
+impl AlnRow {
+    /// Parse one row, rejecting any particle name other than `particle`.
+    pub fn parse(input: &str, particle: &str) -> Result<Self, WireError> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'));
+
+        let (line_no, header) = lines.next().ok_or(WireError::MalformedRow {
+            line: 1,
+            reason: "empty input".into(),
+        })?;
+        match header.split_once(char::is_whitespace) {
+            Some(("row", name)) if name.trim() == particle => {}
+            Some(("row", name)) => return Err(WireError::UnknownVersion(name.trim().into())),
+            _ => {
+                return Err(WireError::MalformedRow {
+                    line: line_no,
+                    reason: format!("expected `row {}`", particle),
+                })
+            }
+        }
+
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut closed = false;
+        for (line_no, line) in lines {
+            if closed {
+                return Err(WireError::MalformedRow {
+                    line: line_no,
+                    reason: "content after `end`".into(),
+                });
+            }
+            if line == "end" {
+                closed = true;
+                continue;
+            }
+            let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if fields.iter().any(|(k, _)| k == key) {
+                return Err(WireError::MalformedRow {
+                    line: line_no,
+                    reason: format!("duplicate field `{}`", key),
+                });
+            }
+            fields.push((key.to_string(), value.trim().to_string()));
+        }
+        if !closed {
+            return Err(WireError::MalformedRow {
+                line: input.lines().count(),
+                reason: "missing `end`".into(),
+            });
+        }
+        Ok(Self { fields })
+    }
+
+    pub fn take(&mut self, field: &str) -> Result<String, WireError> {
+        let idx = self
+            .fields
+            .iter()
+            .position(|(k, _)| k == field)
+            .ok_or_else(|| WireError::MissingField(field.into()))?;
+        Ok(self.fields.remove(idx).1)
+    }
+
+    pub fn take_parsed<T: std::str::FromStr>(&mut self, field: &str) -> Result<T, WireError> {
+        let raw = self.take(field)?;
+        raw.parse::<T>()
+            .map_err(|_| invalid(field, format!("`{}` cannot be parsed", raw)))
+    }
+
+    /// Parse a document of consecutive rows of `particle`; line numbers in errors are document-wide.
+    pub fn parse_all(input: &str, particle: &str) -> Result<Vec<Self>, WireError> {
+        let mut rows = Vec::new();
+        let mut block = String::new();
+        let mut block_start = 0;
+        for (i, line) in input.lines().enumerate() {
+            if block.is_empty() {
+                block_start = i;
+            }
+            block.push_str(line);
+            block.push('\n');
+            if line.trim() == "end" {
+                let row = Self::parse(&block, particle).map_err(|e| match e {
+                    WireError::MalformedRow { line, reason } => WireError::MalformedRow {
+                        line: line + block_start,
+                        reason,
+                    },
+                    other => other,
+                })?;
+                rows.push(row);
+                block.clear();
+            }
+        }
+        if block.lines().any(|l| !l.trim().is_empty() && !l.trim().starts_with('#')) {
+            return Err(WireError::MalformedRow {
+                line: input.lines().count(),
+                reason: "missing `end`".into(),
+            });
+        }
+        Ok(rows)
+    }
+
+    pub fn remaining_keys(&self) -> Vec<String> {
+        self.fields.iter().map(|(k, _)| k.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard() -> ResponseShard {
+        ResponseShard {
+            user_did: "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7".into(),
+            topic: "response_shard_template_v1".into(),
+            triad: Triad { knowledge: 0.94, eco_impact: 0.90, risk_of_harm: 0.13 },
+            residual: Residual {
+                vt: 0.21,
+                coords: vec![
+                    RiskCoord {
+                        var_id: "r_factual".into(),
+                        value: 0.1,
+                        safe: 0.0,
+                        gold: 0.7,
+                        hard: 1.0,
+                        weight: 0.0,
+                    },
+                    RiskCoord {
+                        var_id: "r_eco".into(),
+                        value: 0.3,
+                        safe: 0.0,
+                        gold: 0.7,
+                        hard: 1.0,
+                        weight: 0.0,
+                    },
+                ],
+            },
+            evidence: vec![EvidenceRef::equation("V_t=sum_j w_j r_j")],
+            corridor_tags: vec!["response-shard".into(), "KER".into()],
+        }
+    }
+
+    #[test]
+    fn json_round_trip_uses_schema_names() {
+        let wire = ResponseShardV1::from_shard(&shard(), "0xb2c3").unwrap();
+        let json = wire.to_json().unwrap();
+        assert!(json.contains("\"knowledgefactor01\":0.94"));
+        assert!(json.contains("\"rx_factual01\":0.1"));
+        assert!(json.contains("\"corridortags\":\"response-shard; KER\""));
+
+        let back = ResponseShardV1::from_json(&json).unwrap();
+        assert_eq!(back, wire);
+        let decoded = back.into_shard();
+        assert_eq!(decoded.residual.coords[0].var_id, "r_eco");
+        assert_eq!(decoded.residual.vt, 0.21);
+    }
+
+    #[test]
+    fn parses_multi_row_documents() {
+        let doc = "# corridors\nrow p.v1\n  a 1\nend\n\nrow p.v1\n  a 2\nend\n";
+        let rows = AlnRow::parse_all(doc, "p.v1").unwrap();
+        let values: Vec<u32> = rows.into_iter().map(|mut r| r.take_parsed("a").unwrap()).collect();
+        assert_eq!(values, vec![1, 2]);
+
+        let err = AlnRow::parse_all("row p.v1\n  a 1\nend\nrow p.v1\n  a 2\n", "p.v1").unwrap_err();
+        assert!(matches!(err, WireError::MalformedRow { line: 5, .. }));
+    }
+
+    #[test]
+    fn aln_row_round_trip() {
+        let wire = ResponseShardV1::from_shard(&shard(), "0xb2c3").unwrap();
+        let row = wire.to_aln_row().unwrap();
+        assert!(row.starts_with("row response.shard.v1\n"));
+        assert_eq!(ResponseShardV1::from_aln_row(&row).unwrap(), wire);
+
+        let padded = ResponseShard { topic: " response_shard_template_v1 ".into(), ..shard() };
+        assert!(matches!(
+            ResponseShardV1::from_shard(&padded, "0xb2c3"),
+            Err(WireError::InvalidField { field, .. }) if field == "topic"
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_version_and_fields() {
+        let json = ResponseShardV1::from_shard(&shard(), "0xb2c3").unwrap().to_json().unwrap();
+        let v2 = json.replace("response.shard.v1", "response.shard.v2");
+        assert!(matches!(
+            ResponseShardV1::from_json(&v2),
+            Err(WireError::UnknownVersion(v)) if v == "response.shard.v2"
+        ));
+
+        let extra = json.replacen('{', "{\"triad\":1,", 1);
+        assert!(matches!(
+            ResponseShardV1::from_json(&extra),
+            Err(WireError::UnexpectedField(f)) if f == "triad"
+        ));
+    }
+}
@@ -1,56 +1,6 @@
-use crate::types::{CorridorBands, RiskCoord, Residual, CorridorDecision, KerScore};
+use crate::types::{RiskCoord, Residual, CorridorDecision, KerScore};
 
-#[derive(Clone, Debug)]
-pub struct MarShard {
-    pub mar_id: String,
-    pub basin_id: String,
-    pub lat: f64,
-    pub lon: f64,
-    pub aquifer_unit: String,
-    pub climate_class: String,
-
-    // Hydraulics
-    pub hlr_current: f64,
-    pub hlr_bands: CorridorBands, // var_id="HLR"
-    pub q_in_m3d: f64,
-    pub q_out_m3d: f64,
-    pub res_time_d: f64,
-    pub surcharge_count: u64,
-    pub r_surcharge: RiskCoord,
-
-    // CECs and nutrients
-    pub c_pfas_in_ngl: f64,
-    pub c_pfas_out_ngl: f64,
-    pub r_pfas: RiskCoord,
-
-    pub c_pharma_in_ngl: f64,
-    pub c_pharma_out_ngl: f64,
-    pub r_pharma: RiskCoord,
-
-    pub c_n_in_mgl: f64,
-    pub c_n_out_mgl: f64,
-    pub r_n: RiskCoord,
-
-    pub c_p_in_mgl: f64,
-    pub c_p_out_mgl: f64,
-    pub r_p: RiskCoord,
-
-    // Thermal and redox
-    pub t_plume_c: f64,
-    pub t_bands: CorridorBands, // var_id="TEMP"
-    pub r_thermal: RiskCoord,
-    pub redox_state_mv: f64,
-    pub r_redox: RiskCoord,
-
-    // Fouling
-    pub fouling_index: f64,
-    pub fouling_bands: CorridorBands, // var_id="FOUL"
-    pub cleaning_dose_eq: f64,
-    pub r_foul: RiskCoord,
-
-    // KER scores
-    pub ker: KerScore,
-}
+pub use mar_pilot_sat_cell::mar_shard::{normalize_metric, MarShard};
 
 /// Ensure all critical corridors are present and consistent.
 /// This should be called in CI; if it returns false, build fails.
@@ -74,17 +24,6 @@ pub fn corridor_present(shard: &MarShard) -> bool {
     ok
 }
 
-/// Normalize a raw metric into r_x in [0,1] using corridor bands.
-pub fn normalize_metric(x: f64, bands: &CorridorBands) -> f64 {
-    if x <= bands.safe {
-        0.0
-    } else if x >= bands.hard {
-        1.0
-    } else {
-        (x - bands.safe) / (bands.hard - bands.safe)
-    }
-}
-
 /// Compute V(t) = Σ w_j r_j(t)^2 using weights from bands.
 pub fn compute_residual(risks: &[RiskCoord]) -> Residual {
     let mut vt = 0.0;
@@ -1,6 +1,6 @@
 pub mod types;
 pub mod contracts;
-pub mod io_qpudata; // to be implemented for CSV I/O
+pub use mar_pilot_sat_cell::mar_shard_csv as io_qpudata; // CSV I/O for MarShard particle files
 
 pub use types::*;
 pub use contracts::*;
@@ -1,20 +1,5 @@
-#[derive(Clone, Debug)]
-pub struct CorridorBands {
-    pub var_id: String,    // e.g. "HLR", "PFAS", "TEMP"
-    pub units: String,     // "m/d", "ng/L", "degC"
-    pub safe: f64,         // inner "comfortable" band
-    pub gold: f64,         // regulatory / science "gold" limit
-    pub hard: f64,         // absolute never-exceed limit
-    pub weight_w: f64,     // weight in V(t)
-    pub lyap_channel: u16, // channel index for residual decomposition
-}
-
-#[derive(Clone, Debug)]
-pub struct RiskCoord {
-    pub value: f64,        // normalized r_x in [0,1]
-    pub bands: CorridorBands,
-    pub sigma: f64,        // uncertainty
-}
+// MAR shard types live in the compiled pilot crate; one definition.
+pub use mar_pilot_sat_cell::mar_shard::{CorridorBands, KerScore, RiskCoord};
 
 #[derive(Clone, Debug)]
 pub struct Residual {
@@ -29,10 +14,3 @@ pub struct CorridorDecision {
     pub stop: bool,
     pub reason: String,
 }
-
-#[derive(Clone, Debug)]
-pub struct KerScore {
-    pub knowledge_k: f64,  // 0–1
-    pub eco_impact_e: f64, // 0–1
-    pub risk_r: f64,       // 0–1
-}